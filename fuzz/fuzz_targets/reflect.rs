@@ -0,0 +1,51 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rspirv_reflect::{PipelineReflection, Reflection};
+
+// Runs every query on arbitrary input, none of them may panic regardless of how malformed the
+// module is.
+fuzz_target!(|data: &[u8]| {
+    let mut reflection = match Reflection::new_from_spirv(data) {
+        Ok(reflection) => reflection,
+        Err(_) => return,
+    };
+
+    let _ = reflection.get_vulkan_requirements();
+    let _ = reflection.get_compute_group_size();
+    if let Ok(sets) = reflection.get_descriptor_sets() {
+        for block in sets.values().flat_map(|set| set.values()).flat_map(|d| &d.block) {
+            let _ = block.to_rust("Block");
+            let _ = block.layout_rules();
+        }
+    }
+    let _ = reflection.get_push_constant_range();
+    if let Ok(Some(layout)) = reflection.get_push_constant_layout() {
+        let _ = layout.to_rust("PushConstants");
+        let _ = layout.layout_rules();
+    }
+    let _ = reflection.get_specialization_constants();
+    let _ = reflection.get_types();
+    let _ = reflection.to_json();
+    let _ = PipelineReflection::new(std::slice::from_ref(&reflection));
+
+    for entry_point in reflection.get_entry_points().unwrap_or_default() {
+        let _ = entry_point.stage();
+        let _ = reflection.get_compute_group_size_for_entry_point(&entry_point);
+        let _ = reflection.get_descriptor_sets_for_entry_point(&entry_point);
+        let _ = reflection.get_push_constant_range_for_entry_point(&entry_point);
+        let _ = reflection.get_push_constant_layout_for_entry_point(&entry_point);
+        let _ = reflection.get_interface_variables(&entry_point);
+        let _ = reflection.get_vertex_input_attributes(&entry_point);
+        let _ = reflection.match_interface(&entry_point, &reflection, &entry_point);
+    }
+
+    if reflection
+        .remap_bindings(|set, binding, _| (binding, set))
+        .is_ok()
+    {
+        let _ = reflection.assemble();
+    }
+    // `disassemble()` is left out, it forwards to the rspirv disassembler which expects a
+    // well-formed module
+});
@@ -0,0 +1,114 @@
+//! Read and write access of descriptors
+
+use crate::{DescriptorInfo, DescriptorType, ReflectError, Reflection, Result};
+use rspirv::dr::Operand;
+use rspirv::spirv;
+use std::convert::TryInto;
+
+/// Bitmask of the ways in which a shader accesses a resource
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[repr(transparent)]
+pub struct AccessFlags(pub u32);
+
+impl AccessFlags {
+    pub const NONE: Self = Self(0);
+    pub const READ: Self = Self(1);
+    pub const WRITE: Self = Self(2);
+    pub const READ_WRITE: Self = Self(Self::READ.0 | Self::WRITE.0);
+    /// Accessed through `OpAtomic*` instructions, always combined with `READ` and/or `WRITE`
+    pub const ATOMIC: Self = Self(4);
+}
+
+impl_flags!(AccessFlags, [READ, WRITE, ATOMIC]);
+
+impl Reflection {
+    /// Returns the access permitted by the `NonReadable` and `NonWritable` decorations of
+    /// `var_id`, or of all members of the block backing it.
+    ///
+    /// Descriptors that cannot be written to by shaders are always [`AccessFlags::READ`].
+    pub(crate) fn get_declared_access(
+        &self,
+        var_id: u32,
+        descriptor: &DescriptorInfo,
+    ) -> Result<AccessFlags> {
+        let mut access = match descriptor.ty {
+            DescriptorType::STORAGE_IMAGE
+            | DescriptorType::STORAGE_TEXEL_BUFFER
+            | DescriptorType::STORAGE_BUFFER
+            | DescriptorType::STORAGE_BUFFER_DYNAMIC => AccessFlags::READ_WRITE,
+            _ => return Ok(AccessFlags::READ),
+        };
+
+        if self
+            .find_decoration(var_id, spirv::Decoration::NonReadable)?
+            .is_some()
+        {
+            access &= !AccessFlags::READ;
+        }
+        if self
+            .find_decoration(var_id, spirv::Decoration::NonWritable)?
+            .is_some()
+        {
+            access &= !AccessFlags::WRITE;
+        }
+
+        // Buffer blocks are decorated per member, ie. `readonly buffer` in GLSL or
+        // `StructuredBuffer` in HLSL
+        if let Some(block) = &descriptor.block {
+            let struct_id = self.get_block_type_id(var_id)?;
+            let members = block.members.len().try_into()?;
+            let all_members_decorated = |decoration| -> Result<bool> {
+                for member in 0..members {
+                    if self
+                        .find_member_decoration(struct_id, member, decoration)?
+                        .is_none()
+                    {
+                        return Ok(false);
+                    }
+                }
+                Ok(members > 0)
+            };
+
+            if all_members_decorated(spirv::Decoration::NonReadable)? {
+                access &= !AccessFlags::READ;
+            }
+            if all_members_decorated(spirv::Decoration::NonWritable)? {
+                access &= !AccessFlags::WRITE;
+            }
+        }
+
+        Ok(access)
+    }
+
+    /// Returns the id of the `OpTypeStruct` backing buffer variable `var_id`, looking through
+    /// the variable pointer and any arrays of descriptors
+    fn get_block_type_id(&self, var_id: u32) -> Result<u32> {
+        let var = Self::find_assignment_for(&self.0.types_global_values, var_id)?;
+        let pointer_type_id = var
+            .result_type
+            .ok_or_else(|| ReflectError::VariableWithoutReturnType(var.clone()))?;
+        let pointer_type = Self::find_assignment_for(&self.0.types_global_values, pointer_type_id)?;
+
+        let type_id = get_operand_at!(pointer_type, Operand::IdRef, 1)?;
+        let mut type_instruction = Self::find_assignment_for(&self.0.types_global_values, type_id)?;
+        loop {
+            match type_instruction.class.opcode {
+                spirv::Op::TypeArray | spirv::Op::TypeRuntimeArray => {
+                    let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
+                    type_instruction =
+                        self.find_referenced_type(type_instruction, element_type_id)?;
+                }
+                _ => {
+                    return type_instruction
+                        .result_id
+                        .ok_or_else(|| ReflectError::MissingResultId(type_instruction.clone()))
+                }
+            }
+        }
+    }
+}
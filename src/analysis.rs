@@ -0,0 +1,354 @@
+//! Analysis of the instructions inside function bodies
+
+use crate::{AccessFlags, EntryPoint, ReflectError, Reflection, Result, SamplingFlags};
+use rspirv::dr::{Function, Instruction, Operand};
+use rspirv::spirv;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The functions reachable from an entry point and the global variables that ids in them are
+/// derived from, shared by the analyses of that entry point
+pub(crate) struct FunctionAnalysis<'a> {
+    functions: Vec<&'a Function>,
+    /// See [`Reflection::handle_types()`]
+    handle_types: BTreeSet<u32>,
+    /// See [`Reflection::variable_roots()`]
+    roots: BTreeMap<u32, BTreeSet<u32>>,
+}
+
+impl FunctionAnalysis<'_> {
+    fn instructions(&self) -> impl Iterator<Item = &Instruction> + '_ {
+        self.functions
+            .iter()
+            .flat_map(|f| f.blocks.iter().flat_map(|b| &b.instructions))
+    }
+
+    /// Returns how the entry point accesses each global variable, based on the loads, stores,
+    /// image and atomic instructions in all reachable functions.
+    ///
+    /// Accesses through pointers and handles derived from a variable are attributed to that
+    /// variable, see [`Reflection::variable_roots()`].  Variables that are never accessed are not
+    /// returned.
+    pub(crate) fn variable_accesses(&self) -> Result<BTreeMap<u32, AccessFlags>> {
+        let mut accesses = BTreeMap::<u32, AccessFlags>::new();
+        for inst in self.instructions() {
+            let (operand, access) = match inst.class.opcode {
+                // Loading an image or sampler only produces a handle, the actual access happens
+                // when the handle is passed to an image instruction
+                spirv::Op::Load if Reflection::loads_handle(inst, &self.handle_types) => continue,
+                spirv::Op::Load => (0, AccessFlags::READ),
+                spirv::Op::Store => (0, AccessFlags::WRITE),
+                spirv::Op::CopyMemory | spirv::Op::CopyMemorySized => {
+                    let source = get_operand_at!(inst, Operand::IdRef, 1)?;
+                    for root in self.roots.get(&source).into_iter().flatten() {
+                        *accesses.entry(*root).or_default() |= AccessFlags::READ;
+                    }
+                    (0, AccessFlags::WRITE)
+                }
+                spirv::Op::ImageSampleImplicitLod
+                | spirv::Op::ImageSampleExplicitLod
+                | spirv::Op::ImageSampleDrefImplicitLod
+                | spirv::Op::ImageSampleDrefExplicitLod
+                | spirv::Op::ImageSampleProjImplicitLod
+                | spirv::Op::ImageSampleProjExplicitLod
+                | spirv::Op::ImageSampleProjDrefImplicitLod
+                | spirv::Op::ImageSampleProjDrefExplicitLod
+                | spirv::Op::ImageFetch
+                | spirv::Op::ImageGather
+                | spirv::Op::ImageDrefGather
+                | spirv::Op::ImageRead
+                | spirv::Op::ImageSparseSampleImplicitLod
+                | spirv::Op::ImageSparseSampleExplicitLod
+                | spirv::Op::ImageSparseSampleDrefImplicitLod
+                | spirv::Op::ImageSparseSampleDrefExplicitLod
+                | spirv::Op::ImageSparseFetch
+                | spirv::Op::ImageSparseGather
+                | spirv::Op::ImageSparseDrefGather
+                | spirv::Op::ImageSparseRead => (0, AccessFlags::READ),
+                spirv::Op::ImageWrite => (0, AccessFlags::WRITE),
+                spirv::Op::AtomicLoad => (0, AccessFlags::READ | AccessFlags::ATOMIC),
+                spirv::Op::AtomicStore | spirv::Op::AtomicFlagClear => {
+                    (0, AccessFlags::WRITE | AccessFlags::ATOMIC)
+                }
+                spirv::Op::AtomicExchange
+                | spirv::Op::AtomicCompareExchange
+                | spirv::Op::AtomicCompareExchangeWeak
+                | spirv::Op::AtomicIIncrement
+                | spirv::Op::AtomicIDecrement
+                | spirv::Op::AtomicIAdd
+                | spirv::Op::AtomicISub
+                | spirv::Op::AtomicSMin
+                | spirv::Op::AtomicUMin
+                | spirv::Op::AtomicSMax
+                | spirv::Op::AtomicUMax
+                | spirv::Op::AtomicAnd
+                | spirv::Op::AtomicOr
+                | spirv::Op::AtomicXor
+                | spirv::Op::AtomicFlagTestAndSet
+                | spirv::Op::AtomicFAddEXT
+                | spirv::Op::AtomicFMinEXT
+                | spirv::Op::AtomicFMaxEXT => (0, AccessFlags::READ_WRITE | AccessFlags::ATOMIC),
+                _ => continue,
+            };
+
+            let id = get_operand_at!(inst, Operand::IdRef, operand)?;
+            for root in self.roots.get(&id).into_iter().flatten() {
+                *accesses.entry(*root).or_default() |= access;
+            }
+        }
+
+        Ok(accesses)
+    }
+
+    /// Returns how the entry point samples each global sampler, image and combined image sampler
+    /// variable, based on the image sampling instructions in all reachable functions.
+    ///
+    /// The sampled image passed to these instructions is derived from both the image and the
+    /// sampler variable through `OpSampledImage`, so both are attributed with the sampling.
+    /// Variables that are never sampled are not returned.
+    pub(crate) fn variable_sampling(&self) -> Result<BTreeMap<u32, SamplingFlags>> {
+        let mut sampling = BTreeMap::<u32, SamplingFlags>::new();
+        for inst in self.instructions() {
+            let flags = match inst.class.opcode {
+                spirv::Op::ImageSampleImplicitLod
+                | spirv::Op::ImageSampleExplicitLod
+                | spirv::Op::ImageSampleProjImplicitLod
+                | spirv::Op::ImageSampleProjExplicitLod
+                | spirv::Op::ImageGather
+                | spirv::Op::ImageQueryLod
+                | spirv::Op::ImageSparseSampleImplicitLod
+                | spirv::Op::ImageSparseSampleExplicitLod
+                | spirv::Op::ImageSparseSampleProjImplicitLod
+                | spirv::Op::ImageSparseSampleProjExplicitLod
+                | spirv::Op::ImageSparseGather => SamplingFlags::SAMPLE,
+                spirv::Op::ImageSampleDrefImplicitLod
+                | spirv::Op::ImageSampleDrefExplicitLod
+                | spirv::Op::ImageSampleProjDrefImplicitLod
+                | spirv::Op::ImageSampleProjDrefExplicitLod
+                | spirv::Op::ImageDrefGather
+                | spirv::Op::ImageSparseSampleDrefImplicitLod
+                | spirv::Op::ImageSparseSampleDrefExplicitLod
+                | spirv::Op::ImageSparseSampleProjDrefImplicitLod
+                | spirv::Op::ImageSparseSampleProjDrefExplicitLod
+                | spirv::Op::ImageSparseDrefGather => SamplingFlags::COMPARE,
+                _ => continue,
+            };
+
+            let sampled_image = get_operand_at!(inst, Operand::IdRef, 0)?;
+            for root in self.roots.get(&sampled_image).into_iter().flatten() {
+                *sampling.entry(*root).or_default() |= flags;
+            }
+        }
+
+        Ok(sampling)
+    }
+}
+
+impl Reflection {
+    /// Returns all functions statically reachable from `entry_point`, including the entry point
+    /// function itself.
+    pub(crate) fn reachable_functions(&self, entry_point: &EntryPoint) -> Result<Vec<&Function>> {
+        let functions = self
+            .0
+            .functions
+            .iter()
+            .filter_map(|f| Some((f.def.as_ref()?.result_id?, f)))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut visited = BTreeSet::new();
+        let mut reachable = vec![];
+        let mut stack = vec![entry_point.id];
+
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            let function = functions
+                .get(&id)
+                .ok_or(ReflectError::UnassignedResultId(id))?;
+            for inst in function.blocks.iter().flat_map(|b| &b.instructions) {
+                if inst.class.opcode == spirv::Op::FunctionCall {
+                    stack.push(get_operand_at!(inst, Operand::IdRef, 0)?);
+                }
+            }
+            reachable.push(*function);
+        }
+
+        Ok(reachable)
+    }
+
+    /// Returns the ids of all global variables that are statically used by `entry_point`, meaning
+    /// they are referenced by any function reachable from the entry point or listed in its
+    /// interface.
+    pub(crate) fn statically_used_variables(
+        &self,
+        entry_point: &EntryPoint,
+    ) -> Result<BTreeSet<u32>> {
+        let global_variables = self
+            .0
+            .types_global_values
+            .iter()
+            .filter(|i| i.class.opcode == spirv::Op::Variable)
+            .filter_map(|i| i.result_id)
+            .collect::<BTreeSet<_>>();
+
+        let mut used = entry_point
+            .interface
+            .iter()
+            .copied()
+            .filter(|id| global_variables.contains(id))
+            .collect::<BTreeSet<_>>();
+
+        for function in self.reachable_functions(entry_point)? {
+            for inst in function.blocks.iter().flat_map(|b| &b.instructions) {
+                for operand in &inst.operands {
+                    if let Operand::IdRef(id) = operand {
+                        if global_variables.contains(id) {
+                            used.insert(*id);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(used)
+    }
+
+    /// Finds the functions reachable from `entry_point` and the variables that ids in them are
+    /// derived from
+    pub(crate) fn analyze_functions(
+        &self,
+        entry_point: &EntryPoint,
+    ) -> Result<FunctionAnalysis<'_>> {
+        let functions = self.reachable_functions(entry_point)?;
+        let handle_types = self.handle_types();
+        let roots = self.variable_roots(&functions, &handle_types)?;
+        Ok(FunctionAnalysis {
+            functions,
+            handle_types,
+            roots,
+        })
+    }
+
+    /// Returns the ids of all sampler, image and sampled image types, loading a variable of these
+    /// types only produces a handle to the resource
+    fn handle_types(&self) -> BTreeSet<u32> {
+        self.0
+            .types_global_values
+            .iter()
+            .filter(|i| {
+                matches!(
+                    i.class.opcode,
+                    spirv::Op::TypeImage | spirv::Op::TypeSampler | spirv::Op::TypeSampledImage
+                )
+            })
+            .filter_map(|i| i.result_id)
+            .collect()
+    }
+
+    /// Maps every id derived from a global variable in `functions` to the variables it may
+    /// originate from.
+    ///
+    /// Pointers and image handles derived from a variable through access chains, loads of
+    /// opaque handles, `OpSampledImage`, `OpPhi`, `OpSelect` and function parameters are
+    /// attributed to that variable.
+    fn variable_roots(
+        &self,
+        functions: &[&Function],
+        handle_types: &BTreeSet<u32>,
+    ) -> Result<BTreeMap<u32, BTreeSet<u32>>> {
+        let instructions = || {
+            functions
+                .iter()
+                .flat_map(|f| f.blocks.iter().flat_map(|b| &b.instructions))
+        };
+
+        let parameters = functions
+            .iter()
+            .filter_map(|f| {
+                let parameters = f.parameters.iter().filter_map(|p| p.result_id).collect();
+                Some((f.def.as_ref()?.result_id?, parameters))
+            })
+            .collect::<BTreeMap<u32, Vec<u32>>>();
+
+        let mut roots = self
+            .0
+            .types_global_values
+            .iter()
+            .filter(|i| i.class.opcode == spirv::Op::Variable)
+            .filter_map(|i| i.result_id)
+            .map(|id| (id, std::iter::once(id).collect()))
+            .collect::<BTreeMap<u32, BTreeSet<u32>>>();
+
+        // OpPhi and function parameters can refer to ids that are only derived later on, repeat
+        // until no new derivations are found
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for inst in instructions() {
+                let ids = inst.operands.iter().filter_map(|operand| match operand {
+                    Operand::IdRef(id) => Some(*id),
+                    _ => None,
+                });
+                let sources: Vec<u32> = match inst.class.opcode {
+                    spirv::Op::AccessChain
+                    | spirv::Op::InBoundsAccessChain
+                    | spirv::Op::PtrAccessChain
+                    | spirv::Op::InBoundsPtrAccessChain
+                    | spirv::Op::CopyObject
+                    | spirv::Op::CopyLogical
+                    | spirv::Op::Bitcast
+                    | spirv::Op::ImageTexelPointer
+                    | spirv::Op::Image => ids.take(1).collect(),
+                    spirv::Op::Load if Self::loads_handle(inst, handle_types) => {
+                        ids.take(1).collect()
+                    }
+                    spirv::Op::SampledImage => ids.take(2).collect(),
+                    spirv::Op::Select => ids.skip(1).take(2).collect(),
+                    // Operands are (value, parent block) pairs
+                    spirv::Op::Phi => ids.step_by(2).collect(),
+                    spirv::Op::FunctionCall => {
+                        let callee = get_operand_at!(inst, Operand::IdRef, 0)?;
+                        let callee_parameters = parameters
+                            .get(&callee)
+                            .ok_or(ReflectError::UnassignedResultId(callee))?;
+                        for (&parameter, argument) in callee_parameters.iter().zip(ids.skip(1)) {
+                            changed |= Self::derive_roots(&mut roots, parameter, &[argument]);
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+                if let Some(result_id) = inst.result_id {
+                    changed |= Self::derive_roots(&mut roots, result_id, &sources);
+                }
+            }
+        }
+
+        Ok(roots)
+    }
+
+    fn loads_handle(load: &Instruction, handle_types: &BTreeSet<u32>) -> bool {
+        matches!(load.result_type, Some(ty) if handle_types.contains(&ty))
+    }
+
+    /// Adds the roots of all `sources` to the roots of `target`, returning whether any were added
+    fn derive_roots(
+        roots: &mut BTreeMap<u32, BTreeSet<u32>>,
+        target: u32,
+        sources: &[u32],
+    ) -> bool {
+        let derived = sources
+            .iter()
+            .filter_map(|source| roots.get(source))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        if derived.is_empty() {
+            return false;
+        }
+        let target_roots = roots.entry(target).or_default();
+        let len = target_roots.len();
+        target_roots.extend(derived);
+        target_roots.len() != len
+    }
+}
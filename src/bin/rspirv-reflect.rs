@@ -0,0 +1,232 @@
+//! Command-line tool printing the entry points, descriptor sets and push constants of SPIR-V
+//! modules
+
+use clap::Parser;
+use rspirv_reflect::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Prints the entry points, descriptor sets and push constants of SPIR-V modules
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// SPIR-V modules to reflect
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Print JSON instead of a table
+    #[arg(long, conflicts_with = "check")]
+    json: bool,
+    /// Only report errors, exits with a non-zero status if any module fails to reflect
+    #[arg(long)]
+    check: bool,
+}
+
+#[derive(serde::Serialize)]
+struct EntryPointReport {
+    name: String,
+    execution_model: spirv::ExecutionModel,
+    workgroup_size: Option<(u32, u32, u32)>,
+    /// Reflected per entry point, as modules with several entry points usually declare a push
+    /// constant block for each of them
+    push_constants: Option<PushConstantInfo>,
+}
+
+/// A descriptor without its block layout, in which nested structs are shared between members and
+/// would be written out in full for every use
+#[derive(serde::Serialize)]
+struct DescriptorReport {
+    ty: DescriptorType,
+    binding_count: BindingCount,
+    name: String,
+    stages: ShaderStageFlags,
+    access: AccessFlags,
+    is_statically_used: bool,
+    used_access: AccessFlags,
+}
+
+#[derive(serde::Serialize)]
+struct Report {
+    file: PathBuf,
+    entry_points: Vec<EntryPointReport>,
+    descriptor_sets: BTreeMap<u32, BTreeMap<u32, DescriptorReport>>,
+}
+
+fn reflect(path: &Path) -> Result<Report, Box<dyn std::error::Error>> {
+    let reflection = Reflection::new_from_spirv(&std::fs::read(path)?)?;
+
+    let entry_points = reflection
+        .get_entry_points()?
+        .into_iter()
+        .map(|entry_point| {
+            Ok(EntryPointReport {
+                workgroup_size: reflection.get_compute_group_size_for_entry_point(&entry_point),
+                push_constants: reflection.get_push_constant_range_for_entry_point(&entry_point)?,
+                name: entry_point.name,
+                execution_model: entry_point.execution_model,
+            })
+        })
+        .collect::<Result<_, ReflectError>>()?;
+
+    let descriptor_sets = reflection
+        .get_descriptor_sets()?
+        .into_iter()
+        .map(|(set, bindings)| {
+            let bindings = bindings
+                .into_iter()
+                .map(|(binding, info)| {
+                    (
+                        binding,
+                        DescriptorReport {
+                            ty: info.ty,
+                            binding_count: info.binding_count,
+                            name: info.name,
+                            stages: info.stages,
+                            access: info.access,
+                            is_statically_used: info.is_statically_used,
+                            used_access: info.used_access,
+                        },
+                    )
+                })
+                .collect();
+            (set, bindings)
+        })
+        .collect();
+
+    Ok(Report {
+        file: path.to_owned(),
+        entry_points,
+        descriptor_sets,
+    })
+}
+
+/// Prints `rows` below `header` with every column padded to its widest cell
+fn print_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) {
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let print_row = |cells: [&str; N]| {
+        let line = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("    {}", line.trim_end());
+    };
+    print_row(header);
+    for row in rows {
+        print_row(row.each_ref().map(String::as_str));
+    }
+}
+
+fn print_report(report: &Report) {
+    println!("{}", report.file.display());
+
+    println!("  Entry points:");
+    print_table(
+        ["name", "execution model", "workgroup size"],
+        &report
+            .entry_points
+            .iter()
+            .map(|entry_point| {
+                [
+                    entry_point.name.clone(),
+                    format!("{:?}", entry_point.execution_model),
+                    entry_point
+                        .workgroup_size
+                        .map(|(x, y, z)| format!("{}x{}x{}", x, y, z))
+                        .unwrap_or_default(),
+                ]
+            })
+            .collect::<Vec<_>>(),
+    );
+
+    println!("  Descriptor sets:");
+    print_table(
+        [
+            "set", "binding", "type", "count", "name", "stages", "access", "used",
+        ],
+        &report
+            .descriptor_sets
+            .iter()
+            .flat_map(|(set, bindings)| {
+                bindings.iter().map(move |(binding, info)| {
+                    [
+                        set.to_string(),
+                        binding.to_string(),
+                        format!("{:?}", info.ty),
+                        match info.binding_count {
+                            BindingCount::One => "1".to_owned(),
+                            BindingCount::StaticSized(count) => count.to_string(),
+                            BindingCount::Unbounded => "unbounded".to_owned(),
+                        },
+                        info.name.clone(),
+                        format!("{:?}", info.stages),
+                        format!("{:?}", info.access),
+                        if info.is_statically_used {
+                            format!("{:?}", info.used_access)
+                        } else {
+                            "no".to_owned()
+                        },
+                    ]
+                })
+            })
+            .collect::<Vec<_>>(),
+    );
+
+    println!("  Push constants:");
+    print_table(
+        ["entry point", "offset", "size", "stages"],
+        &report
+            .entry_points
+            .iter()
+            .filter_map(|entry_point| {
+                let range = entry_point.push_constants.as_ref()?;
+                Some([
+                    entry_point.name.clone(),
+                    range.offset.to_string(),
+                    range.size.to_string(),
+                    format!("{:?}", range.stages),
+                ])
+            })
+            .collect::<Vec<_>>(),
+    );
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let mut reports = vec![];
+    let mut failed = false;
+    for path in &args.files {
+        match reflect(path) {
+            Ok(report) => reports.push(report),
+            Err(error) => {
+                eprintln!("{}: {}", path.display(), error);
+                failed = true;
+            }
+        }
+    }
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&reports).expect("Reports are always serializable")
+        );
+    } else if !args.check {
+        for report in &reports {
+            print_report(report);
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,523 @@
+//! Capabilities and extensions declared by a module, and the Vulkan requirements they imply
+
+use crate::{ReflectError, Reflection, Result};
+use rspirv::dr::Operand;
+use rspirv::spirv;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+/// A member of a `VkPhysicalDevice*Features` structure that must be enabled on the device
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VulkanFeature {
+    /// Name of the structure, ie. `VkPhysicalDeviceVulkan12Features`
+    pub structure: Cow<'static, str>,
+    /// Name of the member, ie. `runtimeDescriptorArray`
+    pub name: Cow<'static, str>,
+}
+
+/// A Vulkan device extension that must be enabled on the device
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VulkanExtension {
+    pub name: Cow<'static, str>,
+    /// Vulkan version the extension was promoted to core in, if any
+    pub promoted_to: Option<(u32, u32)>,
+}
+
+impl VulkanExtension {
+    /// Whether the extension is part of core Vulkan `api_version` and need not be enabled
+    pub fn is_core_in(&self, api_version: (u32, u32)) -> bool {
+        matches!(self.promoted_to, Some(version) if version <= api_version)
+    }
+}
+
+/// Vulkan device requirements of a module, following the SPIR-V environment appendix of the
+/// Vulkan specification
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VulkanRequirements {
+    /// Lowest Vulkan version that accepts the SPIR-V version of the module
+    pub api_version: (u32, u32),
+    pub features: BTreeSet<VulkanFeature>,
+    pub extensions: BTreeSet<VulkanExtension>,
+    /// Declared capabilities without known Vulkan requirements, such as OpenCL capabilities
+    pub unknown_capabilities: Vec<spirv::Capability>,
+    /// Declared extensions and extended instruction sets without known Vulkan requirements
+    pub unknown_extensions: Vec<String>,
+}
+
+const CORE: &str = "VkPhysicalDeviceFeatures";
+const VULKAN_11: &str = "VkPhysicalDeviceVulkan11Features";
+const VULKAN_12: &str = "VkPhysicalDeviceVulkan12Features";
+const VULKAN_13: &str = "VkPhysicalDeviceVulkan13Features";
+
+impl VulkanRequirements {
+    fn feature(&mut self, structure: &'static str, name: &'static str) {
+        self.features.insert(VulkanFeature {
+            structure: structure.into(),
+            name: name.into(),
+        });
+    }
+
+    fn extension(&mut self, name: &'static str, promoted_to: Option<(u32, u32)>) {
+        self.extensions.insert(VulkanExtension {
+            name: name.into(),
+            promoted_to,
+        });
+    }
+
+    /// Requires descriptor indexing `feature` from `VK_EXT_descriptor_indexing`
+    fn descriptor_indexing(&mut self, feature: &'static str) {
+        self.feature(VULKAN_12, feature);
+        self.feature(VULKAN_12, "descriptorIndexing");
+        self.extension("VK_EXT_descriptor_indexing", Some((1, 2)));
+    }
+
+    /// Adds the requirements of `capability`, returning `false` if it is not known
+    fn add_capability(&mut self, capability: spirv::Capability) -> bool {
+        use spirv::Capability as C;
+
+        match capability {
+            // Always supported, or implied by the SPIR-V version
+            C::Matrix
+            | C::Shader
+            | C::InputAttachment
+            | C::Sampled1D
+            | C::Image1D
+            | C::SampledBuffer
+            | C::ImageBuffer
+            | C::ImageQuery
+            | C::DerivativeControl
+            | C::StorageImageExtendedFormats
+            | C::GroupNonUniform
+            | C::GroupNonUniformVote
+            | C::GroupNonUniformArithmetic
+            | C::GroupNonUniformBallot
+            | C::GroupNonUniformShuffle
+            | C::GroupNonUniformShuffleRelative
+            | C::GroupNonUniformClustered
+            | C::GroupNonUniformQuad => {}
+            C::Geometry => self.feature(CORE, "geometryShader"),
+            C::Tessellation => self.feature(CORE, "tessellationShader"),
+            C::Float64 => self.feature(CORE, "shaderFloat64"),
+            C::Int64 => self.feature(CORE, "shaderInt64"),
+            C::Int16 => self.feature(CORE, "shaderInt16"),
+            C::TessellationPointSize | C::GeometryPointSize => {
+                self.feature(CORE, "shaderTessellationAndGeometryPointSize")
+            }
+            C::ImageGatherExtended => self.feature(CORE, "shaderImageGatherExtended"),
+            C::StorageImageMultisample | C::ImageMSArray => {
+                self.feature(CORE, "shaderStorageImageMultisample")
+            }
+            C::UniformBufferArrayDynamicIndexing => {
+                self.feature(CORE, "shaderUniformBufferArrayDynamicIndexing")
+            }
+            C::SampledImageArrayDynamicIndexing => {
+                self.feature(CORE, "shaderSampledImageArrayDynamicIndexing")
+            }
+            C::StorageBufferArrayDynamicIndexing => {
+                self.feature(CORE, "shaderStorageBufferArrayDynamicIndexing")
+            }
+            C::StorageImageArrayDynamicIndexing => {
+                self.feature(CORE, "shaderStorageImageArrayDynamicIndexing")
+            }
+            C::ClipDistance => self.feature(CORE, "shaderClipDistance"),
+            C::CullDistance => self.feature(CORE, "shaderCullDistance"),
+            C::ImageCubeArray | C::SampledCubeArray => self.feature(CORE, "imageCubeArray"),
+            C::SampleRateShading | C::InterpolationFunction => {
+                self.feature(CORE, "sampleRateShading")
+            }
+            C::SparseResidency => self.feature(CORE, "shaderResourceResidency"),
+            C::MinLod => self.feature(CORE, "shaderResourceMinLod"),
+            C::StorageImageReadWithoutFormat => {
+                self.feature(CORE, "shaderStorageImageReadWithoutFormat")
+            }
+            C::StorageImageWriteWithoutFormat => {
+                self.feature(CORE, "shaderStorageImageWriteWithoutFormat")
+            }
+            C::MultiViewport => self.feature(CORE, "multiViewport"),
+            C::DrawParameters => {
+                self.feature(VULKAN_11, "shaderDrawParameters");
+                self.extension("VK_KHR_shader_draw_parameters", Some((1, 1)));
+            }
+            C::MultiView => {
+                self.feature(VULKAN_11, "multiview");
+                self.extension("VK_KHR_multiview", Some((1, 1)));
+            }
+            C::DeviceGroup => self.extension("VK_KHR_device_group", Some((1, 1))),
+            C::VariablePointersStorageBuffer => {
+                self.feature(VULKAN_11, "variablePointersStorageBuffer");
+                self.extension("VK_KHR_variable_pointers", Some((1, 1)));
+            }
+            C::VariablePointers => {
+                self.feature(VULKAN_11, "variablePointers");
+                self.extension("VK_KHR_variable_pointers", Some((1, 1)));
+            }
+            C::StorageBuffer16BitAccess => {
+                self.feature(VULKAN_11, "storageBuffer16BitAccess");
+                self.extension("VK_KHR_16bit_storage", Some((1, 1)));
+            }
+            C::UniformAndStorageBuffer16BitAccess => {
+                self.feature(VULKAN_11, "uniformAndStorageBuffer16BitAccess");
+                self.extension("VK_KHR_16bit_storage", Some((1, 1)));
+            }
+            C::StoragePushConstant16 => {
+                self.feature(VULKAN_11, "storagePushConstant16");
+                self.extension("VK_KHR_16bit_storage", Some((1, 1)));
+            }
+            C::StorageInputOutput16 => {
+                self.feature(VULKAN_11, "storageInputOutput16");
+                self.extension("VK_KHR_16bit_storage", Some((1, 1)));
+            }
+            C::StorageBuffer8BitAccess => {
+                self.feature(VULKAN_12, "storageBuffer8BitAccess");
+                self.extension("VK_KHR_8bit_storage", Some((1, 2)));
+            }
+            C::UniformAndStorageBuffer8BitAccess => {
+                self.feature(VULKAN_12, "uniformAndStorageBuffer8BitAccess");
+                self.extension("VK_KHR_8bit_storage", Some((1, 2)));
+            }
+            C::StoragePushConstant8 => {
+                self.feature(VULKAN_12, "storagePushConstant8");
+                self.extension("VK_KHR_8bit_storage", Some((1, 2)));
+            }
+            C::Float16 => {
+                self.feature(VULKAN_12, "shaderFloat16");
+                self.extension("VK_KHR_shader_float16_int8", Some((1, 2)));
+            }
+            C::Int8 => {
+                self.feature(VULKAN_12, "shaderInt8");
+                self.extension("VK_KHR_shader_float16_int8", Some((1, 2)));
+            }
+            C::Int64Atomics => {
+                self.feature(VULKAN_12, "shaderBufferInt64Atomics");
+                self.extension("VK_KHR_shader_atomic_int64", Some((1, 2)));
+            }
+            C::ShaderNonUniform => self.extension("VK_EXT_descriptor_indexing", Some((1, 2))),
+            C::RuntimeDescriptorArray => self.descriptor_indexing("runtimeDescriptorArray"),
+            C::InputAttachmentArrayDynamicIndexing => {
+                self.descriptor_indexing("shaderInputAttachmentArrayDynamicIndexing")
+            }
+            C::UniformTexelBufferArrayDynamicIndexing => {
+                self.descriptor_indexing("shaderUniformTexelBufferArrayDynamicIndexing")
+            }
+            C::StorageTexelBufferArrayDynamicIndexing => {
+                self.descriptor_indexing("shaderStorageTexelBufferArrayDynamicIndexing")
+            }
+            C::UniformBufferArrayNonUniformIndexing => {
+                self.descriptor_indexing("shaderUniformBufferArrayNonUniformIndexing")
+            }
+            C::SampledImageArrayNonUniformIndexing => {
+                self.descriptor_indexing("shaderSampledImageArrayNonUniformIndexing")
+            }
+            C::StorageBufferArrayNonUniformIndexing => {
+                self.descriptor_indexing("shaderStorageBufferArrayNonUniformIndexing")
+            }
+            C::StorageImageArrayNonUniformIndexing => {
+                self.descriptor_indexing("shaderStorageImageArrayNonUniformIndexing")
+            }
+            C::InputAttachmentArrayNonUniformIndexing => {
+                self.descriptor_indexing("shaderInputAttachmentArrayNonUniformIndexing")
+            }
+            C::UniformTexelBufferArrayNonUniformIndexing => {
+                self.descriptor_indexing("shaderUniformTexelBufferArrayNonUniformIndexing")
+            }
+            C::StorageTexelBufferArrayNonUniformIndexing => {
+                self.descriptor_indexing("shaderStorageTexelBufferArrayNonUniformIndexing")
+            }
+            C::VulkanMemoryModel => {
+                self.feature(VULKAN_12, "vulkanMemoryModel");
+                self.extension("VK_KHR_vulkan_memory_model", Some((1, 2)));
+            }
+            C::VulkanMemoryModelDeviceScope => {
+                self.feature(VULKAN_12, "vulkanMemoryModelDeviceScope");
+                self.extension("VK_KHR_vulkan_memory_model", Some((1, 2)));
+            }
+            C::PhysicalStorageBufferAddresses => {
+                self.feature(VULKAN_12, "bufferDeviceAddress");
+                self.extension("VK_KHR_buffer_device_address", Some((1, 2)));
+            }
+            C::ShaderViewportIndex => self.feature(VULKAN_12, "shaderOutputViewportIndex"),
+            C::ShaderLayer => self.feature(VULKAN_12, "shaderOutputLayer"),
+            C::ShaderViewportIndexLayerEXT => {
+                self.extension("VK_EXT_shader_viewport_index_layer", Some((1, 2)))
+            }
+            C::DemoteToHelperInvocation => {
+                self.feature(VULKAN_13, "shaderDemoteToHelperInvocation");
+                self.extension("VK_EXT_shader_demote_to_helper_invocation", Some((1, 3)));
+            }
+            C::DotProduct
+            | C::DotProductInputAll
+            | C::DotProductInput4x8Bit
+            | C::DotProductInput4x8BitPacked => {
+                self.feature(VULKAN_13, "shaderIntegerDotProduct");
+                self.extension("VK_KHR_shader_integer_dot_product", Some((1, 3)));
+            }
+            C::RayQueryKHR => {
+                self.feature("VkPhysicalDeviceRayQueryFeaturesKHR", "rayQuery");
+                self.extension("VK_KHR_ray_query", None);
+            }
+            C::RayTracingKHR => {
+                self.feature(
+                    "VkPhysicalDeviceRayTracingPipelineFeaturesKHR",
+                    "rayTracingPipeline",
+                );
+                self.extension("VK_KHR_ray_tracing_pipeline", None);
+            }
+            C::RayTraversalPrimitiveCullingKHR => {
+                self.feature(
+                    "VkPhysicalDeviceRayTracingPipelineFeaturesKHR",
+                    "rayTraversalPrimitiveCulling",
+                );
+                self.extension("VK_KHR_ray_tracing_pipeline", None);
+            }
+            C::MeshShadingEXT => {
+                self.feature("VkPhysicalDeviceMeshShaderFeaturesEXT", "meshShader");
+                self.extension("VK_EXT_mesh_shader", None);
+            }
+            C::FragmentShadingRateKHR => {
+                self.feature(
+                    "VkPhysicalDeviceFragmentShadingRateFeaturesKHR",
+                    "primitiveFragmentShadingRate",
+                );
+                self.extension("VK_KHR_fragment_shading_rate", None);
+            }
+            C::FragmentShaderPixelInterlockEXT => {
+                self.feature(
+                    "VkPhysicalDeviceFragmentShaderInterlockFeaturesEXT",
+                    "fragmentShaderPixelInterlock",
+                );
+                self.extension("VK_EXT_fragment_shader_interlock", None);
+            }
+            C::FragmentShaderSampleInterlockEXT => {
+                self.feature(
+                    "VkPhysicalDeviceFragmentShaderInterlockFeaturesEXT",
+                    "fragmentShaderSampleInterlock",
+                );
+                self.extension("VK_EXT_fragment_shader_interlock", None);
+            }
+            C::FragmentShaderShadingRateInterlockEXT => {
+                self.feature(
+                    "VkPhysicalDeviceFragmentShaderInterlockFeaturesEXT",
+                    "fragmentShaderShadingRateInterlock",
+                );
+                self.extension("VK_EXT_fragment_shader_interlock", None);
+            }
+            C::FragmentBarycentricKHR => {
+                self.feature(
+                    "VkPhysicalDeviceFragmentShaderBarycentricFeaturesKHR",
+                    "fragmentShaderBarycentric",
+                );
+                self.extension("VK_KHR_fragment_shader_barycentric", None);
+            }
+            C::FragmentDensityEXT => {
+                self.feature(
+                    "VkPhysicalDeviceFragmentDensityMapFeaturesEXT",
+                    "fragmentDensityMap",
+                );
+                self.extension("VK_EXT_fragment_density_map", None);
+            }
+            C::AtomicFloat32AddEXT => {
+                self.feature(
+                    "VkPhysicalDeviceShaderAtomicFloatFeaturesEXT",
+                    "shaderBufferFloat32AtomicAdd",
+                );
+                self.extension("VK_EXT_shader_atomic_float", None);
+            }
+            C::AtomicFloat64AddEXT => {
+                self.feature(
+                    "VkPhysicalDeviceShaderAtomicFloatFeaturesEXT",
+                    "shaderBufferFloat64AtomicAdd",
+                );
+                self.extension("VK_EXT_shader_atomic_float", None);
+            }
+            C::Int64ImageEXT => {
+                self.feature(
+                    "VkPhysicalDeviceShaderImageAtomicInt64FeaturesEXT",
+                    "shaderImageInt64Atomics",
+                );
+                self.extension("VK_EXT_shader_image_atomic_int64", None);
+            }
+            C::ShaderClockKHR => {
+                self.feature(
+                    "VkPhysicalDeviceShaderClockFeaturesKHR",
+                    "shaderSubgroupClock",
+                );
+                self.extension("VK_KHR_shader_clock", None);
+            }
+            C::WorkgroupMemoryExplicitLayoutKHR => {
+                self.feature(
+                    "VkPhysicalDeviceWorkgroupMemoryExplicitLayoutFeaturesKHR",
+                    "workgroupMemoryExplicitLayout",
+                );
+                self.extension("VK_KHR_workgroup_memory_explicit_layout", None);
+            }
+            C::StencilExportEXT => self.extension("VK_EXT_shader_stencil_export", None),
+            C::SubgroupBallotKHR => self.extension("VK_EXT_shader_subgroup_ballot", None),
+            C::SubgroupVoteKHR => self.extension("VK_EXT_shader_subgroup_vote", None),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Adds the requirements of SPIR-V extension `extension`, returning `false` if it is not
+    /// known
+    fn add_extension(&mut self, extension: &str) -> bool {
+        match extension {
+            "SPV_KHR_storage_buffer_storage_class" => {
+                self.extension("VK_KHR_storage_buffer_storage_class", Some((1, 1)))
+            }
+            "SPV_KHR_variable_pointers" => self.extension("VK_KHR_variable_pointers", Some((1, 1))),
+            "SPV_KHR_16bit_storage" => self.extension("VK_KHR_16bit_storage", Some((1, 1))),
+            "SPV_KHR_multiview" => self.extension("VK_KHR_multiview", Some((1, 1))),
+            "SPV_KHR_shader_draw_parameters" => {
+                self.extension("VK_KHR_shader_draw_parameters", Some((1, 1)))
+            }
+            "SPV_KHR_device_group" => self.extension("VK_KHR_device_group", Some((1, 1))),
+            "SPV_KHR_8bit_storage" => self.extension("VK_KHR_8bit_storage", Some((1, 2))),
+            "SPV_KHR_float_controls" => {
+                self.extension("VK_KHR_shader_float_controls", Some((1, 2)))
+            }
+            "SPV_EXT_descriptor_indexing" => {
+                self.feature(VULKAN_12, "descriptorIndexing");
+                self.extension("VK_EXT_descriptor_indexing", Some((1, 2)));
+            }
+            "SPV_KHR_vulkan_memory_model" => {
+                self.feature(VULKAN_12, "vulkanMemoryModel");
+                self.extension("VK_KHR_vulkan_memory_model", Some((1, 2)));
+            }
+            "SPV_KHR_physical_storage_buffer" => {
+                self.feature(VULKAN_12, "bufferDeviceAddress");
+                self.extension("VK_KHR_buffer_device_address", Some((1, 2)));
+            }
+            "SPV_EXT_shader_viewport_index_layer" => {
+                self.extension("VK_EXT_shader_viewport_index_layer", Some((1, 2)))
+            }
+            "SPV_EXT_demote_to_helper_invocation" => {
+                self.extension("VK_EXT_shader_demote_to_helper_invocation", Some((1, 3)))
+            }
+            "SPV_KHR_integer_dot_product" => {
+                self.extension("VK_KHR_shader_integer_dot_product", Some((1, 3)))
+            }
+            "SPV_KHR_non_semantic_info" => {
+                self.extension("VK_KHR_shader_non_semantic_info", Some((1, 3)))
+            }
+            "SPV_KHR_terminate_invocation" => {
+                self.extension("VK_KHR_shader_terminate_invocation", Some((1, 3)))
+            }
+            "SPV_KHR_ray_query" => self.extension("VK_KHR_ray_query", None),
+            "SPV_KHR_ray_tracing" => self.extension("VK_KHR_ray_tracing_pipeline", None),
+            "SPV_EXT_mesh_shader" => self.extension("VK_EXT_mesh_shader", None),
+            "SPV_KHR_fragment_shading_rate" => self.extension("VK_KHR_fragment_shading_rate", None),
+            "SPV_EXT_fragment_shader_interlock" => {
+                self.extension("VK_EXT_fragment_shader_interlock", None)
+            }
+            "SPV_KHR_fragment_shader_barycentric" => {
+                self.extension("VK_KHR_fragment_shader_barycentric", None)
+            }
+            "SPV_EXT_fragment_invocation_density" => {
+                self.extension("VK_EXT_fragment_density_map", None)
+            }
+            "SPV_EXT_shader_atomic_float_add" => self.extension("VK_EXT_shader_atomic_float", None),
+            "SPV_EXT_shader_image_int64" => {
+                self.extension("VK_EXT_shader_image_atomic_int64", None)
+            }
+            "SPV_KHR_shader_clock" => self.extension("VK_KHR_shader_clock", None),
+            "SPV_KHR_workgroup_memory_explicit_layout" => {
+                self.extension("VK_KHR_workgroup_memory_explicit_layout", None)
+            }
+            "SPV_EXT_shader_stencil_export" => self.extension("VK_EXT_shader_stencil_export", None),
+            "SPV_KHR_shader_ballot" => self.extension("VK_EXT_shader_subgroup_ballot", None),
+            "SPV_KHR_subgroup_vote" => self.extension("VK_EXT_shader_subgroup_vote", None),
+            "SPV_GOOGLE_decorate_string" => self.extension("VK_GOOGLE_decorate_string", None),
+            "SPV_GOOGLE_hlsl_functionality1" => {
+                self.extension("VK_GOOGLE_hlsl_functionality1", None)
+            }
+            "SPV_GOOGLE_user_type" => self.extension("VK_GOOGLE_user_type", None),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Reflection {
+    /// Returns the capabilities declared through `OpCapability`
+    pub fn get_capabilities(&self) -> Result<Vec<spirv::Capability>> {
+        self.0
+            .capabilities
+            .iter()
+            .map(|i| get_operand_at!(i, Operand::Capability, 0))
+            .collect()
+    }
+
+    /// Returns the SPIR-V extensions declared through `OpExtension`, ie.
+    /// `SPV_EXT_descriptor_indexing`
+    pub fn get_extensions(&self) -> Result<Vec<String>> {
+        self.0
+            .extensions
+            .iter()
+            .map(|i| Ok(get_ref_operand_at!(i, Operand::LiteralString, 0)?.clone()))
+            .collect()
+    }
+
+    /// Returns the extended instruction sets imported through `OpExtInstImport`, ie.
+    /// `GLSL.std.450`
+    pub fn get_extended_instruction_sets(&self) -> Result<Vec<String>> {
+        self.0
+            .ext_inst_imports
+            .iter()
+            .map(|i| Ok(get_ref_operand_at!(i, Operand::LiteralString, 0)?.clone()))
+            .collect()
+    }
+
+    /// Returns the Vulkan version, device features and device extensions needed to consume the
+    /// module, based on its SPIR-V version, capabilities, extensions and extended instruction
+    /// sets.
+    ///
+    /// Anything without a known mapping is listed in
+    /// [`VulkanRequirements::unknown_capabilities`] and
+    /// [`VulkanRequirements::unknown_extensions`] rather than ignored.
+    pub fn get_vulkan_requirements(&self) -> Result<VulkanRequirements> {
+        let api_version = match self
+            .0
+            .header
+            .as_ref()
+            .ok_or(ReflectError::MissingHeader)?
+            .version()
+        {
+            (1, 0) => (1, 0),
+            (1, 1..=3) => (1, 1),
+            (1, 4..=5) => (1, 2),
+            _ => (1, 3),
+        };
+
+        let mut requirements = VulkanRequirements {
+            api_version,
+            features: BTreeSet::new(),
+            extensions: BTreeSet::new(),
+            unknown_capabilities: vec![],
+            unknown_extensions: vec![],
+        };
+
+        for capability in self.get_capabilities()? {
+            if !requirements.add_capability(capability) {
+                requirements.unknown_capabilities.push(capability);
+            }
+        }
+        for extension in self.get_extensions()? {
+            if !requirements.add_extension(&extension) {
+                requirements.unknown_extensions.push(extension);
+            }
+        }
+        for set in self.get_extended_instruction_sets()? {
+            if set.starts_with("NonSemantic.") {
+                requirements.extension("VK_KHR_shader_non_semantic_info", Some((1, 3)));
+            } else if set != "GLSL.std.450" {
+                requirements.unknown_extensions.push(set);
+            }
+        }
+
+        Ok(requirements)
+    }
+}
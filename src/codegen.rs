@@ -0,0 +1,453 @@
+//! Generating Rust structs matching the memory layout of blocks
+
+use crate::{ArrayLength, NumericType, ReflectError, Result, ScalarType, StructLayout, TypeLayout};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Rust type occupying exactly `size` bytes
+#[derive(Clone)]
+struct RustType {
+    name: String,
+    size: u32,
+    align: u32,
+}
+
+#[derive(Default)]
+struct Generator {
+    /// Source of every generated item
+    items: Vec<String>,
+    /// Layout and requested size of every struct declared so far, with the resulting Rust type
+    structs: Vec<(StructLayout, u32, RustType)>,
+    names: BTreeSet<String>,
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Replaces all characters that cannot appear in a Rust identifier with underscores, falling
+/// back to `fallback` for empty names
+fn identifier(name: &str, fallback: &str) -> String {
+    let mut identifier = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if identifier.chars().all(|c| c == '_') {
+        identifier = fallback.to_owned();
+    }
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    if KEYWORDS.contains(&identifier.as_str()) {
+        identifier.insert_str(0, "r#");
+    }
+    identifier
+}
+
+/// Appends a numeric suffix to `name` until it does not occur in `names`, and adds it
+fn unique(names: &mut BTreeSet<String>, name: String) -> String {
+    let mut unique = name.clone();
+    let mut suffix = 1;
+    while names.contains(&unique) {
+        unique = format!("{}{}", name, suffix);
+        suffix += 1;
+    }
+    names.insert(unique.clone());
+    unique
+}
+
+fn round_up(size: u32, align: u32) -> Option<u32> {
+    Some(size.checked_add(align - 1)? / align * align)
+}
+
+impl Generator {
+    fn unrepresentable(layout: &StructLayout, reason: String) -> ReflectError {
+        ReflectError::UnrepresentableLayout(layout.name.clone(), reason)
+    }
+
+    /// Returns the Rust scalar type and its size in bytes.  There is no stable `f16` that
+    /// implements `bytemuck::Pod`, half floats are represented by their bits.
+    fn scalar(layout: &StructLayout, scalar: ScalarType) -> Result<(&'static str, u32)> {
+        Ok(match scalar {
+            ScalarType::Float { width: 16 } => ("u16", 2),
+            ScalarType::Float { width: 32 } => ("f32", 4),
+            ScalarType::Float { width: 64 } => ("f64", 8),
+            ScalarType::Int {
+                width: 8,
+                signed: true,
+            } => ("i8", 1),
+            ScalarType::Int {
+                width: 8,
+                signed: false,
+            } => ("u8", 1),
+            ScalarType::Int {
+                width: 16,
+                signed: true,
+            } => ("i16", 2),
+            ScalarType::Int {
+                width: 16,
+                signed: false,
+            } => ("u16", 2),
+            ScalarType::Int {
+                width: 32,
+                signed: true,
+            } => ("i32", 4),
+            ScalarType::Int {
+                width: 32,
+                signed: false,
+            } => ("u32", 4),
+            ScalarType::Int {
+                width: 64,
+                signed: true,
+            } => ("i64", 8),
+            ScalarType::Int {
+                width: 64,
+                signed: false,
+            } => ("u64", 8),
+            scalar => {
+                return Err(Self::unrepresentable(
+                    layout,
+                    format!("{:?} has no Rust equivalent", scalar),
+                ))
+            }
+        })
+    }
+
+    /// Returns `count` elements of `scalar` placed `stride` bytes apart, padding every element
+    /// with additional scalars if needed
+    fn numeric(
+        layout: &StructLayout,
+        ty: NumericType,
+        count: u32,
+        stride: u32,
+    ) -> Result<RustType> {
+        let (scalar, scalar_size) = Self::scalar(layout, ty.scalar)?;
+        if !stride.is_multiple_of(scalar_size) || stride < ty.vector_size * scalar_size {
+            return Err(Self::unrepresentable(
+                layout,
+                format!(
+                    "stride {} of {:?} is not a multiple of its scalar",
+                    stride, ty
+                ),
+            ));
+        }
+        let elements = stride / scalar_size;
+        let element = if elements == 1 {
+            scalar.to_owned()
+        } else {
+            format!("[{}; {}]", scalar, elements)
+        };
+        Ok(RustType {
+            name: if count == 1 {
+                element
+            } else {
+                format!("[{}; {}]", element, count)
+            },
+            size: stride * count,
+            align: scalar_size,
+        })
+    }
+
+    /// Returns the Rust type of `ty`, padded to `size` bytes if it is an array element
+    fn rust_type(&mut self, layout: &StructLayout, ty: &TypeLayout, size: u32) -> Result<RustType> {
+        match ty {
+            TypeLayout::Numeric(ty) => Self::numeric(layout, *ty, 1, size),
+            TypeLayout::Matrix {
+                ty,
+                stride,
+                row_major,
+            } => {
+                let (major_count, minor_count) = if *row_major {
+                    (ty.vector_size, ty.column_count)
+                } else {
+                    (ty.column_count, ty.vector_size)
+                };
+                let minor = NumericType {
+                    vector_size: minor_count,
+                    column_count: 1,
+                    ..*ty
+                };
+                let matrix = Self::numeric(layout, minor, major_count, *stride)?;
+                if matrix.size != size {
+                    return Err(Self::unrepresentable(
+                        layout,
+                        format!("padding {:?} from {} to {} bytes", ty, matrix.size, size),
+                    ));
+                }
+                Ok(matrix)
+            }
+            TypeLayout::Array {
+                element,
+                length: Some(ArrayLength::Constant(length)),
+                stride,
+            } => {
+                let element = self.rust_type(layout, element, *stride)?;
+                if element.size != *stride {
+                    return Err(Self::unrepresentable(
+                        layout,
+                        format!("array stride {} is smaller than its elements", stride),
+                    ));
+                }
+                Ok(RustType {
+                    name: format!("[{}; {}]", element.name, length),
+                    size: stride * length,
+                    align: element.align,
+                })
+            }
+            TypeLayout::Array { length: None, .. } => Err(Self::unrepresentable(
+                layout,
+                "runtime arrays can only appear at the end of a block".to_owned(),
+            )),
+            TypeLayout::Array {
+                length: Some(length),
+                ..
+            } => Err(Self::unrepresentable(
+                layout,
+                format!("array length {:?} depends on specialization", length),
+            )),
+            TypeLayout::Struct(nested) => self.declare_struct(nested, None, size),
+            // Device addresses obtained through `vkGetBufferDeviceAddress`
+            TypeLayout::PhysicalPointer => Ok(RustType {
+                name: "u64".to_owned(),
+                size: 8,
+                align: 8,
+            }),
+        }
+    }
+
+    /// Declares a struct for `layout` which is at least `size` bytes large, or reuses an earlier
+    /// declaration with the same layout and size
+    fn declare_struct(
+        &mut self,
+        layout: &StructLayout,
+        name: Option<&str>,
+        size: u32,
+    ) -> Result<RustType> {
+        // Nested structs are shared by every member of their type, and are only declared once
+        // rather than walked again for each occurrence
+        if name.is_none() {
+            if let Some((_, _, ty)) = self
+                .structs
+                .iter()
+                .find(|(declared, requested, _)| declared == layout && *requested == size)
+            {
+                return Ok(ty.clone());
+            }
+        }
+        let requested = size;
+
+        let mut members = layout.members.iter().collect::<Vec<_>>();
+        members.sort_by_key(|member| member.offset);
+
+        // A trailing runtime array is not part of the struct, its elements follow the struct
+        let runtime_array = match members.last() {
+            Some(member) => match &member.ty {
+                TypeLayout::Array {
+                    element,
+                    length: None,
+                    stride,
+                } => Some((
+                    member.name.clone(),
+                    self.rust_type(layout, element, *stride)?,
+                )),
+                _ => None,
+            },
+            None => None,
+        };
+        if runtime_array.is_some() {
+            members.pop();
+        }
+
+        let mut field_names = BTreeSet::new();
+        let mut fields = vec![];
+        let mut align = 1;
+        let mut end = 0;
+        let mut padding = 0;
+        for (idx, member) in members.iter().enumerate() {
+            if member.offset < end {
+                return Err(Self::unrepresentable(
+                    layout,
+                    format!("member `{}` overlaps the previous member", member.name),
+                ));
+            }
+            if member.offset > end {
+                fields.push((
+                    unique(&mut field_names, format!("_padding{}", padding)),
+                    format!("[u8; {}]", member.offset - end),
+                    None,
+                ));
+                padding += 1;
+            }
+
+            let ty = self.rust_type(layout, &member.ty, member.size)?;
+            // `#[repr(C)]` would insert implicit padding before misaligned members
+            if !member.offset.is_multiple_of(ty.align) {
+                return Err(Self::unrepresentable(
+                    layout,
+                    format!(
+                        "member `{}` at offset {} is not aligned to {} bytes",
+                        member.name, member.offset, ty.align
+                    ),
+                ));
+            }
+            let field = unique(
+                &mut field_names,
+                identifier(&member.name, &format!("member{}", idx)),
+            );
+            fields.push((field, ty.name, Some(member.offset)));
+            align = align.max(ty.align);
+            end = member.offset.checked_add(ty.size).ok_or_else(|| {
+                Self::unrepresentable(layout, format!("member `{}` overflows", member.name))
+            })?;
+        }
+
+        let size = round_up(size.max(end), align).ok_or_else(|| {
+            Self::unrepresentable(layout, format!("size {} overflows", size.max(end)))
+        })?;
+        if let Some((_, element)) = &runtime_array {
+            align = align.max(element.align);
+        }
+        if !size.is_multiple_of(align) {
+            return Err(Self::unrepresentable(
+                layout,
+                format!("size {} is not a multiple of alignment {}", size, align),
+            ));
+        }
+        if size > end {
+            fields.push((
+                unique(&mut field_names, format!("_padding{}", padding)),
+                format!("[u8; {}]", size - end),
+                None,
+            ));
+        }
+
+        if name.is_none() {
+            if let Some((_, _, ty)) = self
+                .structs
+                .iter()
+                .find(|(declared, _, ty)| declared == layout && ty.size == size)
+            {
+                let ty = ty.clone();
+                self.structs.push((layout.clone(), requested, ty.clone()));
+                return Ok(ty);
+            }
+        }
+
+        // Debug names of HLSL blocks are prefixed with `type.ConstantBuffer.` and the like
+        let name_given = name.is_some();
+        let name = match name {
+            Some(name) => name.to_owned(),
+            None => identifier(layout.name.rsplit('.').next().unwrap_or_default(), "Block"),
+        };
+        let name = unique(&mut self.names, name);
+
+        let mut source = String::new();
+        if !layout.name.is_empty() {
+            writeln!(source, "/// Layout of `{}`", layout.name).unwrap();
+        }
+        if let Some((member, element)) = &runtime_array {
+            if !layout.name.is_empty() {
+                writeln!(source, "///").unwrap();
+            }
+            if member.is_empty() {
+                writeln!(
+                    source,
+                    "/// Followed by a runtime array of `{}`",
+                    element.name
+                )
+            } else {
+                writeln!(
+                    source,
+                    "/// Followed by the runtime array `{}` of `{}`",
+                    member, element.name
+                )
+            }
+            .unwrap();
+        }
+        writeln!(source, "#[repr(C)]").unwrap();
+        writeln!(
+            source,
+            "#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]"
+        )
+        .unwrap();
+        if fields.is_empty() {
+            writeln!(source, "pub struct {} {{}}", name).unwrap();
+        } else {
+            writeln!(source, "pub struct {} {{", name).unwrap();
+            for (field, ty, _) in &fields {
+                writeln!(source, "    pub {}: {},", field, ty).unwrap();
+            }
+            writeln!(source, "}}").unwrap();
+        }
+        writeln!(source).unwrap();
+        // Only the block itself is declared with a name, nested structs are padded to their
+        // array stride or the offset of the next member instead
+        if name_given {
+            writeln!(source, "impl {} {{", name).unwrap();
+            writeln!(
+                source,
+                "    /// Size of the block in bytes, excluding the padding at the end of this struct"
+            )
+            .unwrap();
+            writeln!(source, "    pub const SIZE: usize = {};", layout.size).unwrap();
+            writeln!(source, "}}").unwrap();
+            writeln!(source).unwrap();
+        }
+        writeln!(
+            source,
+            "const _: () = assert!(::std::mem::size_of::<{}>() == {});",
+            name, size
+        )
+        .unwrap();
+        for (field, _, offset) in &fields {
+            if let Some(offset) = offset {
+                writeln!(
+                    source,
+                    "const _: () = assert!(::std::mem::offset_of!({}, {}) == {});",
+                    name, field, offset
+                )
+                .unwrap();
+            }
+        }
+        self.items.push(source);
+
+        let ty = RustType { name, size, align };
+        self.structs.push((layout.clone(), requested, ty.clone()));
+        Ok(ty)
+    }
+}
+
+impl StructLayout {
+    /// Returns Rust source declaring a `#[repr(C)]` struct named `name` with the same memory
+    /// layout, along with the structs nested within it.
+    ///
+    /// Gaps between members become explicit `_padding` byte arrays, so the structs can derive
+    /// `bytemuck::Pod` and `bytemuck::Zeroable`.  Vectors and matrices become arrays of scalars,
+    /// with additional scalars padding array elements and matrix columns (or rows) up to their
+    /// stride.  Every struct is followed by compile-time assertions of its size and member
+    /// offsets, so that generated code that no longer matches the shader fails to build.
+    ///
+    /// The size of a struct is rounded up to the alignment of its members, which can make it
+    /// larger than [`Self::size`].  The struct named `name` therefore gets a `SIZE` constant
+    /// holding [`Self::size`], and uploads have to be truncated to it, ie.
+    /// `&bytemuck::bytes_of(&value)[..PushConstants::SIZE]` as the tail padding may not fit in
+    /// the push constant range or buffer.  A trailing runtime array is not part of the struct,
+    /// its element type is mentioned in the documentation of the struct instead.
+    ///
+    /// The generated arrays and padding can have any length, while `bytemuck` only implements
+    /// `Pod` for arrays of up to 32 elements and a few larger powers of two by default.  Crates
+    /// including the generated code need to enable the `min_const_generics` feature of
+    /// `bytemuck` to support all other lengths.
+    ///
+    /// Fails with [`ReflectError::UnrepresentableLayout`] if a member cannot be placed at its
+    /// offset, for example because it is not aligned or overlaps another member.
+    pub fn to_rust(&self, name: &str) -> Result<String> {
+        let mut generator = Generator::default();
+        generator.declare_struct(self, Some(&identifier(name, "Block")), self.size)?;
+        Ok(generator.items.join("\n"))
+    }
+}
@@ -0,0 +1,119 @@
+//! Mirror of `VkFormat`
+
+/// These are bit-exact with ash and the Vulkan specification,
+/// they're mirrored here to prevent a dependency on ash.
+///
+/// Only the formats that can be derived from shader types are mirrored.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[repr(transparent)]
+pub struct Format(pub u32);
+
+impl Format {
+    pub const UNDEFINED: Self = Self(0);
+
+    pub const R8_UINT: Self = Self(13);
+    pub const R8_SINT: Self = Self(14);
+    pub const R8G8_UINT: Self = Self(20);
+    pub const R8G8_SINT: Self = Self(21);
+    pub const R8G8B8_UINT: Self = Self(27);
+    pub const R8G8B8_SINT: Self = Self(28);
+    pub const R8G8B8A8_UINT: Self = Self(41);
+    pub const R8G8B8A8_SINT: Self = Self(42);
+
+    pub const R16_UINT: Self = Self(74);
+    pub const R16_SINT: Self = Self(75);
+    pub const R16_SFLOAT: Self = Self(76);
+    pub const R16G16_UINT: Self = Self(81);
+    pub const R16G16_SINT: Self = Self(82);
+    pub const R16G16_SFLOAT: Self = Self(83);
+    pub const R16G16B16_UINT: Self = Self(88);
+    pub const R16G16B16_SINT: Self = Self(89);
+    pub const R16G16B16_SFLOAT: Self = Self(90);
+    pub const R16G16B16A16_UINT: Self = Self(95);
+    pub const R16G16B16A16_SINT: Self = Self(96);
+    pub const R16G16B16A16_SFLOAT: Self = Self(97);
+
+    pub const R32_UINT: Self = Self(98);
+    pub const R32_SINT: Self = Self(99);
+    pub const R32_SFLOAT: Self = Self(100);
+    pub const R32G32_UINT: Self = Self(101);
+    pub const R32G32_SINT: Self = Self(102);
+    pub const R32G32_SFLOAT: Self = Self(103);
+    pub const R32G32B32_UINT: Self = Self(104);
+    pub const R32G32B32_SINT: Self = Self(105);
+    pub const R32G32B32_SFLOAT: Self = Self(106);
+    pub const R32G32B32A32_UINT: Self = Self(107);
+    pub const R32G32B32A32_SINT: Self = Self(108);
+    pub const R32G32B32A32_SFLOAT: Self = Self(109);
+
+    pub const R64_UINT: Self = Self(110);
+    pub const R64_SINT: Self = Self(111);
+    pub const R64_SFLOAT: Self = Self(112);
+    pub const R64G64_UINT: Self = Self(113);
+    pub const R64G64_SINT: Self = Self(114);
+    pub const R64G64_SFLOAT: Self = Self(115);
+    pub const R64G64B64_UINT: Self = Self(116);
+    pub const R64G64B64_SINT: Self = Self(117);
+    pub const R64G64B64_SFLOAT: Self = Self(118);
+    pub const R64G64B64A64_UINT: Self = Self(119);
+    pub const R64G64B64A64_SINT: Self = Self(120);
+    pub const R64G64B64A64_SFLOAT: Self = Self(121);
+}
+
+impl std::fmt::Debug for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            Self::UNDEFINED => "UNDEFINED",
+            Self::R8_UINT => "R8_UINT",
+            Self::R8_SINT => "R8_SINT",
+            Self::R8G8_UINT => "R8G8_UINT",
+            Self::R8G8_SINT => "R8G8_SINT",
+            Self::R8G8B8_UINT => "R8G8B8_UINT",
+            Self::R8G8B8_SINT => "R8G8B8_SINT",
+            Self::R8G8B8A8_UINT => "R8G8B8A8_UINT",
+            Self::R8G8B8A8_SINT => "R8G8B8A8_SINT",
+            Self::R16_UINT => "R16_UINT",
+            Self::R16_SINT => "R16_SINT",
+            Self::R16_SFLOAT => "R16_SFLOAT",
+            Self::R16G16_UINT => "R16G16_UINT",
+            Self::R16G16_SINT => "R16G16_SINT",
+            Self::R16G16_SFLOAT => "R16G16_SFLOAT",
+            Self::R16G16B16_UINT => "R16G16B16_UINT",
+            Self::R16G16B16_SINT => "R16G16B16_SINT",
+            Self::R16G16B16_SFLOAT => "R16G16B16_SFLOAT",
+            Self::R16G16B16A16_UINT => "R16G16B16A16_UINT",
+            Self::R16G16B16A16_SINT => "R16G16B16A16_SINT",
+            Self::R16G16B16A16_SFLOAT => "R16G16B16A16_SFLOAT",
+            Self::R32_UINT => "R32_UINT",
+            Self::R32_SINT => "R32_SINT",
+            Self::R32_SFLOAT => "R32_SFLOAT",
+            Self::R32G32_UINT => "R32G32_UINT",
+            Self::R32G32_SINT => "R32G32_SINT",
+            Self::R32G32_SFLOAT => "R32G32_SFLOAT",
+            Self::R32G32B32_UINT => "R32G32B32_UINT",
+            Self::R32G32B32_SINT => "R32G32B32_SINT",
+            Self::R32G32B32_SFLOAT => "R32G32B32_SFLOAT",
+            Self::R32G32B32A32_UINT => "R32G32B32A32_UINT",
+            Self::R32G32B32A32_SINT => "R32G32B32A32_SINT",
+            Self::R32G32B32A32_SFLOAT => "R32G32B32A32_SFLOAT",
+            Self::R64_UINT => "R64_UINT",
+            Self::R64_SINT => "R64_SINT",
+            Self::R64_SFLOAT => "R64_SFLOAT",
+            Self::R64G64_UINT => "R64G64_UINT",
+            Self::R64G64_SINT => "R64G64_SINT",
+            Self::R64G64_SFLOAT => "R64G64_SFLOAT",
+            Self::R64G64B64_UINT => "R64G64B64_UINT",
+            Self::R64G64B64_SINT => "R64G64B64_SINT",
+            Self::R64G64B64_SFLOAT => "R64G64B64_SFLOAT",
+            Self::R64G64B64A64_UINT => "R64G64B64A64_UINT",
+            Self::R64G64B64A64_SINT => "R64G64B64A64_SINT",
+            Self::R64G64B64A64_SFLOAT => "R64G64B64A64_SFLOAT",
+            _ => "(UNKNOWN)",
+        })
+    }
+}
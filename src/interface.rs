@@ -0,0 +1,339 @@
+//! Reflection of stage input and output variables
+
+use crate::{EntryPoint, NumericType, ReflectError, Reflection, Result};
+use rspirv::dr::{Instruction, Operand};
+use rspirv::spirv;
+use std::convert::TryInto;
+
+/// Bitmask of the interpolation decorations of an interface variable
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[repr(transparent)]
+pub struct InterpolationFlags(pub u32);
+
+impl InterpolationFlags {
+    pub const NONE: Self = Self(0);
+    pub const FLAT: Self = Self(1);
+    pub const NO_PERSPECTIVE: Self = Self(2);
+    pub const CENTROID: Self = Self(4);
+    pub const SAMPLE: Self = Self(8);
+}
+
+impl_flags!(InterpolationFlags, [FLAT, NO_PERSPECTIVE, CENTROID, SAMPLE]);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InterfaceVariable {
+    /// Debug name of the variable.
+    ///
+    /// Members of interface blocks are flattened into separate variables named
+    /// `block_instance.member`, or just `member` if the block instance is unnamed (ie.
+    /// `gl_PerVertex`).  Nested structs are flattened the same way, with arrays of them unrolled
+    /// into `block_instance.member[i].field`.
+    pub name: String,
+    /// Either [`spirv::StorageClass::Input`] or [`spirv::StorageClass::Output`]
+    pub storage_class: spirv::StorageClass,
+    /// First location occupied by this variable, `None` for builtins
+    pub location: Option<u32>,
+    /// First component occupied by this variable within its location
+    pub component: u32,
+    pub builtin: Option<spirv::BuiltIn>,
+    pub ty: NumericType,
+    /// Array dimensions, outermost first.  Empty if the variable is not an array.
+    ///
+    /// For tessellation, geometry and mesh shaders the outermost dimension is the per-vertex (or
+    /// per-primitive) array, which does not consume additional locations.
+    pub array_dims: Vec<u32>,
+    pub interpolation: InterpolationFlags,
+    /// Whether this is a per-patch variable of a tessellation shader, which is not arrayed
+    pub patch: bool,
+    /// Whether this is a per-primitive output of a mesh shader or the matching fragment shader
+    /// input
+    pub per_primitive: bool,
+}
+
+impl InterfaceVariable {
+    pub fn is_builtin(&self) -> bool {
+        self.builtin.is_some()
+    }
+}
+
+/// Upper bound on the number of variables a single interface block is flattened into, which
+/// grows exponentially with the nesting depth of arrays of structs
+const MAX_FLATTENED_MEMBERS: usize = 4096;
+
+/// Properties of an interface block variable shared by all of its flattened members
+struct Block<'a> {
+    /// Prefix of the member names, ie. `block_instance` or `block_instance.member[i]`
+    name: &'a str,
+    storage_class: spirv::StorageClass,
+    array_dims: &'a [u32],
+    interpolation: InterpolationFlags,
+    patch: bool,
+    per_primitive: bool,
+}
+
+impl Reflection {
+    /// Returns whether `id`, or member `member` of struct `id`, is decorated with `decoration`
+    fn has_interface_decoration(
+        &self,
+        id: u32,
+        member: Option<u32>,
+        decoration: spirv::Decoration,
+    ) -> Result<bool> {
+        Ok(match member {
+            Some(member) => self
+                .find_member_decoration(id, member, decoration)?
+                .is_some(),
+            None => self.find_decoration(id, decoration)?.is_some(),
+        })
+    }
+
+    fn get_interpolation_flags(&self, id: u32, member: Option<u32>) -> Result<InterpolationFlags> {
+        let mut flags = InterpolationFlags::NONE;
+        for &(decoration, flag) in &[
+            (spirv::Decoration::Flat, InterpolationFlags::FLAT),
+            (
+                spirv::Decoration::NoPerspective,
+                InterpolationFlags::NO_PERSPECTIVE,
+            ),
+            (spirv::Decoration::Centroid, InterpolationFlags::CENTROID),
+            (spirv::Decoration::Sample, InterpolationFlags::SAMPLE),
+        ] {
+            if self.has_interface_decoration(id, member, decoration)? {
+                flags |= flag;
+            }
+        }
+        Ok(flags)
+    }
+
+    /// Returns all `Input` and `Output` variables in the interface of `entry_point`, in the order
+    /// they are listed in its `OpEntryPoint` instruction
+    pub fn get_interface_variables(
+        &self,
+        entry_point: &EntryPoint,
+    ) -> Result<Vec<InterfaceVariable>> {
+        let names = self.get_names()?;
+        let mut variables = vec![];
+
+        for &var_id in &entry_point.interface {
+            let var = Self::find_assignment_for(&self.0.types_global_values, var_id)?;
+            if var.class.opcode != spirv::Op::Variable {
+                continue;
+            }
+
+            let storage_class = get_operand_at!(var, Operand::StorageClass, 0)?;
+            if storage_class != spirv::StorageClass::Input
+                && storage_class != spirv::StorageClass::Output
+            {
+                // SPIR-V 1.4 and up list all global variables in the interface
+                continue;
+            }
+
+            let type_id = var
+                .result_type
+                .ok_or_else(|| ReflectError::VariableWithoutReturnType(var.clone()))?;
+            let pointer_type = Self::find_assignment_for(&self.0.types_global_values, type_id)?;
+            let pointee_type_id = get_operand_at!(pointer_type, Operand::IdRef, 1)?;
+            let (type_instruction, array_dims) = self.peel_arrays(pointee_type_id)?;
+
+            let name = names.get(&var_id).cloned().unwrap_or_default();
+            let location = self.get_decoration_literal(var_id, spirv::Decoration::Location)?;
+            let component = self
+                .get_decoration_literal(var_id, spirv::Decoration::Component)?
+                .unwrap_or(0);
+            let builtin = match self.find_decoration(var_id, spirv::Decoration::BuiltIn)? {
+                Some(decoration) => Some(get_operand_at!(decoration, Operand::BuiltIn, 2)?),
+                None => None,
+            };
+            let interpolation = self.get_interpolation_flags(var_id, None)?;
+            let patch = self.has_interface_decoration(var_id, None, spirv::Decoration::Patch)?;
+            let per_primitive =
+                self.has_interface_decoration(var_id, None, spirv::Decoration::PerPrimitiveEXT)?;
+
+            if type_instruction.class.opcode != spirv::Op::TypeStruct {
+                variables.push(InterfaceVariable {
+                    name,
+                    storage_class,
+                    location: location.filter(|_| builtin.is_none()),
+                    component,
+                    builtin,
+                    ty: self.get_numeric_type(type_instruction)?,
+                    array_dims,
+                    interpolation,
+                    patch,
+                    per_primitive,
+                });
+                continue;
+            }
+
+            // Flatten interface blocks, members without an explicit location are assigned
+            // consecutive locations starting at the location of the block
+            let block = Block {
+                name: &name,
+                storage_class,
+                array_dims: &array_dims,
+                interpolation,
+                patch,
+                per_primitive,
+            };
+            let mut next_location = location;
+            let first = variables.len();
+            self.flatten_block(
+                type_instruction,
+                &block,
+                &mut next_location,
+                &mut variables,
+                first + MAX_FLATTENED_MEMBERS,
+            )?;
+        }
+
+        Ok(variables)
+    }
+
+    /// Appends the members of interface block `struct_type` to `variables`, recursing into
+    /// nested structs and unrolling arrays of them into one set of members per element
+    fn flatten_block(
+        &self,
+        struct_type: &Instruction,
+        block: &Block<'_>,
+        next_location: &mut Option<u32>,
+        variables: &mut Vec<InterfaceVariable>,
+        max_variables: usize,
+    ) -> Result<()> {
+        let struct_id = struct_type
+            .result_id
+            .ok_or_else(|| ReflectError::MissingResultId(struct_type.clone()))?;
+        for idx in 0..struct_type.operands.len() {
+            let member_type_id = get_operand_at!(struct_type, Operand::IdRef, idx)?;
+            let member = idx.try_into()?;
+            self.find_referenced_type(struct_type, member_type_id)?;
+            let (member_type, member_array_dims) = self.peel_arrays(member_type_id)?;
+
+            let member_name = self.get_member_name(struct_id, member)?.unwrap_or_default();
+            let name = if block.name.is_empty() {
+                member_name.to_owned()
+            } else {
+                format!("{}.{}", block.name, member_name)
+            };
+            let builtin =
+                match self.find_member_decoration(struct_id, member, spirv::Decoration::BuiltIn)? {
+                    Some(decoration) => Some(get_operand_at!(decoration, Operand::BuiltIn, 3)?),
+                    None => None,
+                };
+            let location = match self.find_member_decoration(
+                struct_id,
+                member,
+                spirv::Decoration::Location,
+            )? {
+                Some(decoration) => Some(get_operand_at!(decoration, Operand::LiteralBit32, 3)?),
+                None => *next_location,
+            }
+            .filter(|_| builtin.is_none());
+            let component = match self.find_member_decoration(
+                struct_id,
+                member,
+                spirv::Decoration::Component,
+            )? {
+                Some(decoration) => get_operand_at!(decoration, Operand::LiteralBit32, 3)?,
+                None => 0,
+            };
+            let interpolation =
+                block.interpolation | self.get_interpolation_flags(struct_id, Some(member))?;
+            let patch = block.patch
+                || self.has_interface_decoration(
+                    struct_id,
+                    Some(member),
+                    spirv::Decoration::Patch,
+                )?;
+            let per_primitive = block.per_primitive
+                || self.has_interface_decoration(
+                    struct_id,
+                    Some(member),
+                    spirv::Decoration::PerPrimitiveEXT,
+                )?;
+
+            if member_type.class.opcode == spirv::Op::TypeStruct {
+                // Elements of arrays of structs occupy consecutive locations one after the
+                // other, so they are flattened into `member[i].field` rather than arrays of
+                // each field
+                let element_count = member_array_dims
+                    .iter()
+                    .try_fold(1usize, |count, &dim| count.checked_mul(dim as usize))
+                    .filter(|&count| variables.len().saturating_add(count) <= max_variables)
+                    .ok_or_else(|| {
+                        ReflectError::TooManyInterfaceMembers(
+                            block.name.to_owned(),
+                            MAX_FLATTENED_MEMBERS,
+                        )
+                    })?;
+                *next_location = location;
+                for element in 0..element_count {
+                    let mut element_name = name.clone();
+                    let mut stride = element_count;
+                    for &dim in &member_array_dims {
+                        stride /= dim as usize;
+                        element_name += &format!("[{}]", element / stride % dim as usize);
+                    }
+                    let element_block = Block {
+                        name: &element_name,
+                        interpolation,
+                        patch,
+                        per_primitive,
+                        ..*block
+                    };
+                    self.flatten_block(
+                        member_type,
+                        &element_block,
+                        next_location,
+                        variables,
+                        max_variables,
+                    )?;
+                }
+                continue;
+            }
+
+            if variables.len() >= max_variables {
+                return Err(ReflectError::TooManyInterfaceMembers(
+                    block.name.to_owned(),
+                    MAX_FLATTENED_MEMBERS,
+                ));
+            }
+            let ty = self.get_numeric_type(member_type)?;
+            *next_location = match location {
+                Some(location) => Some(
+                    member_array_dims
+                        .iter()
+                        .try_fold(ty.location_count(), |count, &dim| count.checked_mul(dim))
+                        .and_then(|count| location.checked_add(count))
+                        .ok_or_else(|| ReflectError::LocationOverflow(name.clone()))?,
+                ),
+                None => None,
+            };
+
+            variables.push(InterfaceVariable {
+                name,
+                storage_class: block.storage_class,
+                location,
+                component,
+                builtin,
+                ty,
+                array_dims: block
+                    .array_dims
+                    .iter()
+                    .chain(&member_array_dims)
+                    .copied()
+                    .collect(),
+                interpolation,
+                patch,
+                per_primitive,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,801 @@
+//! Export in the JSON format of `spirv-cross --reflect`
+
+use crate::{
+    AccessFlags, DescriptorType, EntryPoint, ImageType, NumericType, ReflectError, Reflection,
+    Result, ScalarType, SpecializationConstantValue,
+};
+use rspirv::dr::{Instruction, Operand};
+use rspirv::spirv;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryInto;
+
+/// Array dimensions of a type, innermost first as in SPIRV-Cross
+#[derive(Default)]
+struct ArrayDims {
+    sizes: Vec<u32>,
+    /// `false` for sizes given by the id of a specialization constant
+    literal: Vec<bool>,
+}
+
+impl ArrayDims {
+    fn insert_into(self, object: &mut Map<String, Value>) {
+        if !self.sizes.is_empty() {
+            object.insert("array".to_owned(), json!(self.sizes));
+            object.insert("array_size_is_literal".to_owned(), json!(self.literal));
+        }
+    }
+}
+
+impl Reflection {
+    /// Exports the module in the JSON schema of `spirv-cross --reflect`, with the `entryPoints`,
+    /// `types`, `inputs`, `outputs`, `subpass_inputs`, `textures`, `separate_images`,
+    /// `separate_samplers`, `images`, `ssbos`, `ubos`, `push_constants`,
+    /// `acceleration_structures` and `specialization_constants` sections.
+    ///
+    /// Like SPIRV-Cross, descriptors are reflected for the whole module while `inputs` and
+    /// `outputs` belong to the first entry point.  The last constant decorated with the
+    /// `WorkgroupSize` builtin applies to every compute entry point, even in modules linked from
+    /// multiple shaders that each declare one; [`Self::get_compute_group_size_for_entry_point()`]
+    /// returns the size declared by the entry point itself.
+    pub fn to_json(&self) -> Result<Value> {
+        let mut root = Map::new();
+
+        let entry_points = self.get_entry_points()?;
+        let mut sorted_entry_points = entry_points.iter().collect::<Vec<_>>();
+        sorted_entry_points.sort_by(|a, b| {
+            (a.execution_model as u32, &a.name).cmp(&(b.execution_model as u32, &b.name))
+        });
+        root.insert(
+            "entryPoints".to_owned(),
+            sorted_entry_points
+                .into_iter()
+                .map(|entry_point| {
+                    let mut object = Map::new();
+                    object.insert("name".to_owned(), json!(entry_point.name));
+                    object.insert(
+                        "mode".to_owned(),
+                        json!(Self::json_mode(entry_point.execution_model)),
+                    );
+                    if let Some(size) = self.json_workgroup_size(entry_point)? {
+                        object.insert(
+                            "workgroup_size".to_owned(),
+                            json!(size.iter().map(|&(value, _)| value).collect::<Vec<_>>()),
+                        );
+                        object.insert(
+                            "workgroup_size_is_spec_constant_id".to_owned(),
+                            json!(size.iter().map(|&(_, spec)| spec).collect::<Vec<_>>()),
+                        );
+                    }
+                    Ok(Value::Object(object))
+                })
+                .collect::<Result<_>>()?,
+        );
+
+        let mut types = Map::new();
+        for instruction in &self.0.types_global_values {
+            match instruction.class.opcode {
+                spirv::Op::TypeStruct => {
+                    let id = Self::result_id(instruction)?;
+                    types.insert(format!("_{}", id), self.json_struct(instruction)?);
+                }
+                // Physical storage buffer pointers refer to types that are not reflected
+                // otherwise, unless they point to a struct
+                spirv::Op::TypePointer
+                    if get_operand_at!(instruction, Operand::StorageClass, 0)?
+                        == spirv::StorageClass::PhysicalStorageBuffer =>
+                {
+                    let pointee_id = get_operand_at!(instruction, Operand::IdRef, 1)?;
+                    let pointee = self.find_referenced_type(instruction, pointee_id)?;
+                    if pointee.class.opcode != spirv::Op::TypeStruct {
+                        types.insert(format!("_{}", pointee_id), self.json_pointee(pointee)?);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !types.is_empty() {
+            root.insert("types".to_owned(), Value::Object(types));
+        }
+
+        let mut sections = BTreeMap::<&str, Vec<Value>>::new();
+        let mut push = |section, resource| {
+            sections
+                .entry(section)
+                .or_default()
+                .push(Value::Object(resource))
+        };
+
+        if let Some(entry_point) = entry_points.first() {
+            for &var_id in &entry_point.interface {
+                let var = Self::find_assignment_for(&self.0.types_global_values, var_id)?;
+                let section = match get_operand_at!(var, Operand::StorageClass, 0)? {
+                    spirv::StorageClass::Input => "inputs",
+                    spirv::StorageClass::Output => "outputs",
+                    _ => continue,
+                };
+                let (base, array) = self.json_variable_type(var)?;
+                if self
+                    .find_decoration(var_id, spirv::Decoration::BuiltIn)?
+                    .is_some()
+                    || (base.class.opcode == spirv::Op::TypeStruct
+                        && self.is_builtin_block(base)?)
+                {
+                    continue;
+                }
+
+                let mut resource = Map::new();
+                resource.insert("type".to_owned(), json!(self.json_type_name(base)?));
+                // Unlike plain structs, blocks are named after their type
+                let name = match base.result_id {
+                    Some(struct_id)
+                        if base.class.opcode == spirv::Op::TypeStruct
+                            && self
+                                .find_decoration(struct_id, spirv::Decoration::Block)?
+                                .is_some() =>
+                    {
+                        self.json_block_name(var_id, struct_id)?
+                    }
+                    _ => self.json_variable_name(var_id)?,
+                };
+                resource.insert("name".to_owned(), json!(name));
+                array.insert_into(&mut resource);
+                for (key, decoration) in [
+                    ("location", spirv::Decoration::Location),
+                    ("component", spirv::Decoration::Component),
+                ] {
+                    if let Some(value) = self.get_decoration_literal(var_id, decoration)? {
+                        resource.insert(key.to_owned(), json!(value));
+                    }
+                }
+                push(section, resource);
+            }
+        }
+
+        let descriptor_sets = self.get_descriptor_sets()?;
+        let ssbo_instance_names = self.json_ssbo_instance_names()?;
+        for var in &self.0.types_global_values {
+            if var.class.opcode != spirv::Op::Variable {
+                continue;
+            }
+            let var_id = Self::result_id(var)?;
+
+            if get_operand_at!(var, Operand::StorageClass, 0)? == spirv::StorageClass::PushConstant
+            {
+                let (base, _) = self.json_variable_type(var)?;
+                let mut resource = Map::new();
+                resource.insert("type".to_owned(), json!(self.json_type_name(base)?));
+                resource.insert("name".to_owned(), json!(self.json_variable_name(var_id)?));
+                resource.insert("push_constant".to_owned(), json!(true));
+                push("push_constants", resource);
+                continue;
+            }
+
+            let (set, binding) = match (
+                self.get_decoration_literal(var_id, spirv::Decoration::DescriptorSet)?,
+                self.get_decoration_literal(var_id, spirv::Decoration::Binding)?,
+            ) {
+                (Some(set), Some(binding)) => (set, binding),
+                _ => continue,
+            };
+            let info = match descriptor_sets
+                .get(&set)
+                .and_then(|bindings| bindings.get(&binding))
+            {
+                Some(info) => info,
+                None => continue,
+            };
+
+            let (base, array) = self.json_variable_type(var)?;
+            let mut resource = Map::new();
+            resource.insert("type".to_owned(), json!(self.json_type_name(base)?));
+            resource.insert("name".to_owned(), json!(self.json_variable_name(var_id)?));
+            array.insert_into(&mut resource);
+
+            let section = match info.ty {
+                DescriptorType::UNIFORM_BUFFER | DescriptorType::STORAGE_BUFFER => {
+                    let block = info
+                        .block
+                        .as_ref()
+                        .ok_or_else(|| ReflectError::UnknownStruct(base.clone()))?;
+                    // Buffers are named after their block rather than the variable, except for
+                    // storage buffers sharing their block with other storage buffers
+                    if info.ty == DescriptorType::UNIFORM_BUFFER || !ssbo_instance_names {
+                        resource.insert(
+                            "name".to_owned(),
+                            json!(self.json_block_name(var_id, Self::result_id(base)?)?),
+                        );
+                    }
+                    resource.insert("block_size".to_owned(), json!(block.size));
+                    if info.ty == DescriptorType::UNIFORM_BUFFER {
+                        "ubos"
+                    } else {
+                        Self::json_access(&mut resource, info.access);
+                        "ssbos"
+                    }
+                }
+                DescriptorType::SAMPLER => "separate_samplers",
+                DescriptorType::ACCELERATION_STRUCTURE_KHR => "acceleration_structures",
+                DescriptorType::INPUT_ATTACHMENT => {
+                    if let Some(index) = self
+                        .get_decoration_literal(var_id, spirv::Decoration::InputAttachmentIndex)?
+                    {
+                        resource.insert("input_attachment_index".to_owned(), json!(index));
+                    }
+                    "subpass_inputs"
+                }
+                // Unlike buffers, SPIRV-Cross does not report the access of images
+                DescriptorType::STORAGE_IMAGE | DescriptorType::STORAGE_TEXEL_BUFFER => {
+                    if let Some(image) = &info.image {
+                        resource.insert("format".to_owned(), json!(Self::json_format(image)));
+                    }
+                    "images"
+                }
+                _ if base.class.opcode == spirv::Op::TypeSampledImage => "textures",
+                _ => "separate_images",
+            };
+
+            resource.insert("set".to_owned(), json!(set));
+            resource.insert("binding".to_owned(), json!(binding));
+            push(section, resource);
+        }
+
+        for (section, resources) in sections {
+            root.insert(section.to_owned(), Value::Array(resources));
+        }
+
+        // SPIRV-Cross lists specialization constants by result id rather than `SpecId`
+        let spec_constant_ids = self.json_spec_constant_ids()?;
+        let mut specialization_constants = self
+            .get_specialization_constants()?
+            .into_iter()
+            .map(|constant| {
+                let id = spec_constant_ids[&constant.spec_id];
+                let mut object = Map::new();
+                object.insert("name".to_owned(), json!(constant.name));
+                object.insert("id".to_owned(), json!(constant.spec_id));
+                object.insert(
+                    "type".to_owned(),
+                    json!(Self::json_numeric_name(&NumericType {
+                        scalar: constant.ty,
+                        vector_size: 1,
+                        column_count: 1,
+                    })),
+                );
+                object.insert("variable_id".to_owned(), json!(id));
+                // Only the defaults of booleans and 32-bit constants are reported
+                let default_value = match (constant.ty.width(), constant.default_value) {
+                    (_, SpecializationConstantValue::Bool(value)) => Some(json!(value)),
+                    (Some(32), SpecializationConstantValue::Int(value)) => Some(json!(value)),
+                    (Some(32), SpecializationConstantValue::UInt(value)) => Some(json!(value)),
+                    (Some(32), SpecializationConstantValue::Float(value)) => Some(json!(value)),
+                    _ => None,
+                };
+                if let Some(default_value) = default_value {
+                    object.insert("default_value".to_owned(), default_value);
+                }
+                (id, Value::Object(object))
+            })
+            .collect::<Vec<_>>();
+        if !specialization_constants.is_empty() {
+            specialization_constants.sort_by_key(|&(id, _)| id);
+            root.insert(
+                "specialization_constants".to_owned(),
+                specialization_constants
+                    .into_iter()
+                    .map(|(_, constant)| constant)
+                    .collect(),
+            );
+        }
+
+        Ok(Value::Object(root))
+    }
+
+    /// Returns the result ids of all constants decorated with a `SpecId`, by their `SpecId`
+    fn json_spec_constant_ids(&self) -> Result<BTreeMap<u32, u32>> {
+        let mut ids = BTreeMap::new();
+        for annotation in &self.0.annotations {
+            if annotation.class.opcode == spirv::Op::Decorate
+                && get_operand_at!(annotation, Operand::Decoration, 1)? == spirv::Decoration::SpecId
+            {
+                ids.insert(
+                    get_operand_at!(annotation, Operand::LiteralBit32, 2)?,
+                    get_operand_at!(annotation, Operand::IdRef, 0)?,
+                );
+            }
+        }
+        Ok(ids)
+    }
+
+    fn result_id(instruction: &Instruction) -> Result<u32> {
+        instruction
+            .result_id
+            .ok_or_else(|| ReflectError::MissingResultId(instruction.clone()))
+    }
+
+    /// Returns the workgroup size of a compute `entry_point` as pairs of a size and whether it
+    /// is given by a specialization constant, in which case SPIRV-Cross reports its `SpecId`
+    /// instead.  Sizes that are not declared are reported as 0.
+    ///
+    /// The last constant decorated with the `WorkgroupSize` builtin overrides the execution
+    /// modes of every entry point, and `LocalSizeId` overrides `LocalSize`.
+    fn json_workgroup_size(&self, entry_point: &EntryPoint) -> Result<Option<[(u32, bool); 3]>> {
+        if entry_point.execution_model != spirv::ExecutionModel::GLCompute {
+            return Ok(None);
+        }
+
+        let mut workgroup_size = None;
+        for instruction in &self.0.types_global_values {
+            if matches!(
+                instruction.class.opcode,
+                spirv::Op::ConstantComposite | spirv::Op::SpecConstantComposite
+            ) {
+                let id = Self::result_id(instruction)?;
+                if let Some(decoration) = self.find_decoration(id, spirv::Decoration::BuiltIn)? {
+                    if get_operand_at!(decoration, Operand::BuiltIn, 2)?
+                        == spirv::BuiltIn::WorkgroupSize
+                    {
+                        workgroup_size = Some(instruction);
+                    }
+                }
+            }
+        }
+        if let Some(instruction) = workgroup_size {
+            return self.json_workgroup_size_constants(instruction, 0).map(Some);
+        }
+
+        for instruction in &self.0.execution_modes {
+            if instruction.class.opcode == spirv::Op::ExecutionModeId
+                && get_operand_at!(instruction, Operand::IdRef, 0)? == entry_point.id
+                && get_operand_at!(instruction, Operand::ExecutionMode, 1)?
+                    == spirv::ExecutionMode::LocalSizeId
+            {
+                return self.json_workgroup_size_constants(instruction, 2).map(Some);
+            }
+        }
+
+        let (x, y, z) = self
+            .get_compute_group_size_for_entry_point(entry_point)
+            .unwrap_or_default();
+        Ok(Some([(x, false), (y, false), (z, false)]))
+    }
+
+    /// Returns the values of the three workgroup size constants referenced by `instruction`
+    /// starting at operand `first`, or their `SpecId` if they are specialization constants
+    fn json_workgroup_size_constants(
+        &self,
+        instruction: &Instruction,
+        first: usize,
+    ) -> Result<[(u32, bool); 3]> {
+        let mut size = [(0, false); 3];
+        for (i, size) in size.iter_mut().enumerate() {
+            // rspirv's builder encodes the ids of `OpExecutionModeId` as literals
+            let id = match instruction.operands.get(first + i) {
+                Some(&Operand::LiteralBit32(id)) => id,
+                _ => get_operand_at!(instruction, Operand::IdRef, first + i)?,
+            };
+            let constant = Self::find_assignment_for(&self.0.types_global_values, id)?;
+            *size = match constant.class.opcode {
+                spirv::Op::Constant => {
+                    (get_operand_at!(constant, Operand::LiteralBit32, 0)?, false)
+                }
+                // `OpSpecConstantOp` results have no `SpecId` and are reported as 0
+                _ => (
+                    self.get_decoration_literal(id, spirv::Decoration::SpecId)?
+                        .unwrap_or(0),
+                    true,
+                ),
+            };
+        }
+        Ok(size)
+    }
+
+    fn json_name(&self, id: u32) -> Result<&str> {
+        Ok(self.get_name(id)?.unwrap_or_default())
+    }
+
+    /// Returns the name of `var_id`, or `_<id>` if it has none
+    fn json_variable_name(&self, var_id: u32) -> Result<String> {
+        Ok(match self.json_name(var_id)? {
+            "" => format!("_{}", var_id),
+            name => name.to_owned(),
+        })
+    }
+
+    /// Returns the name of the block `struct_id` of `var_id`, falling back to the name of the
+    /// variable and `_<struct id>_<variable id>`
+    fn json_block_name(&self, var_id: u32, struct_id: u32) -> Result<String> {
+        Ok(
+            match (self.json_name(struct_id)?, self.json_name(var_id)?) {
+                ("", "") => format!("_{}_{}", struct_id, var_id),
+                ("", name) | (name, _) => name.to_owned(),
+            },
+        )
+    }
+
+    /// Whether storage buffers are named after their variable rather than their block, as
+    /// SPIRV-Cross does for HLSL where many buffers commonly share a block.  Without `OpSource`
+    /// this is the case when any two storage buffers share a block.
+    fn json_ssbo_instance_names(&self) -> Result<bool> {
+        let mut language = None;
+        for instruction in &self.0.debug_string_source {
+            if instruction.class.opcode == spirv::Op::Source {
+                language = Some(get_operand_at!(instruction, Operand::SourceLanguage, 0)?);
+            }
+        }
+        match language {
+            Some(spirv::SourceLanguage::HLSL) => return Ok(true),
+            Some(spirv::SourceLanguage::GLSL | spirv::SourceLanguage::ESSL) => return Ok(false),
+            _ => {}
+        }
+
+        let mut blocks = BTreeSet::new();
+        for var in &self.0.types_global_values {
+            if var.class.opcode != spirv::Op::Variable {
+                continue;
+            }
+            let (base, _) = self.json_variable_type(var)?;
+            if base.class.opcode != spirv::Op::TypeStruct {
+                continue;
+            }
+            let struct_id = Self::result_id(base)?;
+            let ssbo = match get_operand_at!(var, Operand::StorageClass, 0)? {
+                spirv::StorageClass::StorageBuffer => true,
+                spirv::StorageClass::Uniform => self
+                    .find_decoration(struct_id, spirv::Decoration::BufferBlock)?
+                    .is_some(),
+                _ => false,
+            };
+            if ssbo && !blocks.insert(struct_id) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Whether `struct_instruction` is a builtin block such as `gl_PerVertex`, whose variables
+    /// SPIRV-Cross does not reflect
+    fn is_builtin_block(&self, struct_instruction: &Instruction) -> Result<bool> {
+        let id = Self::result_id(struct_instruction)?;
+        Ok(Self::find_annotations_for_id(&self.0.annotations, id)?
+            .iter()
+            .any(|i| {
+                i.class.opcode == spirv::Op::MemberDecorate
+                    && matches!(
+                        i.operands.get(2),
+                        Some(Operand::Decoration(spirv::Decoration::BuiltIn))
+                    )
+            }))
+    }
+
+    /// Inserts the SPIRV-Cross `readonly` and `writeonly` flags of a storage buffer
+    fn json_access(resource: &mut Map<String, Value>, access: AccessFlags) {
+        if !access.contains(AccessFlags::WRITE) {
+            resource.insert("readonly".to_owned(), json!(true));
+        }
+        if !access.contains(AccessFlags::READ) {
+            resource.insert("writeonly".to_owned(), json!(true));
+        }
+    }
+
+    /// Returns the pointee type of `var` with all array levels stripped
+    fn json_variable_type(&self, var: &Instruction) -> Result<(&Instruction, ArrayDims)> {
+        let type_id = var
+            .result_type
+            .ok_or_else(|| ReflectError::VariableWithoutReturnType(var.clone()))?;
+        let pointer_type = Self::find_assignment_for(&self.0.types_global_values, type_id)?;
+        let pointee_type_id = get_operand_at!(pointer_type, Operand::IdRef, 1)?;
+        let pointee_type = self.find_referenced_type(pointer_type, pointee_type_id)?;
+        self.json_peel_arrays(pointee_type)
+    }
+
+    /// Strips all `OpTypeArray` and `OpTypeRuntimeArray` levels from `type_instruction`
+    fn json_peel_arrays<'a>(
+        &'a self,
+        mut type_instruction: &'a Instruction,
+    ) -> Result<(&'a Instruction, ArrayDims)> {
+        let mut dims = ArrayDims::default();
+        loop {
+            match type_instruction.class.opcode {
+                spirv::Op::TypeArray => {
+                    let length_id = get_operand_at!(type_instruction, Operand::IdRef, 1)?;
+                    let length = Self::find_assignment_for(&self.0.types_global_values, length_id)?;
+                    if length.class.opcode == spirv::Op::Constant {
+                        dims.sizes.push(self.get_array_length(type_instruction)?);
+                        dims.literal.push(true);
+                    } else {
+                        dims.sizes.push(length_id);
+                        dims.literal.push(false);
+                    }
+                }
+                spirv::Op::TypeRuntimeArray => {
+                    dims.sizes.push(0);
+                    dims.literal.push(true);
+                }
+                _ => break,
+            }
+            let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
+            type_instruction = self.find_referenced_type(type_instruction, element_type_id)?;
+        }
+        dims.sizes.reverse();
+        dims.literal.reverse();
+        Ok((type_instruction, dims))
+    }
+
+    fn json_struct(&self, struct_instruction: &Instruction) -> Result<Value> {
+        let struct_id = Self::result_id(struct_instruction)?;
+
+        let mut members = vec![];
+        for idx in 0..struct_instruction.operands.len() {
+            let member_type_id = get_operand_at!(struct_instruction, Operand::IdRef, idx)?;
+            let member = idx.try_into()?;
+            let member_type = self.find_referenced_type(struct_instruction, member_type_id)?;
+            let (base, array) = self.json_peel_arrays(member_type)?;
+
+            let mut object = Map::new();
+            let name = match self.get_member_name(struct_id, member)? {
+                Some(name) if !name.is_empty() => name.to_owned(),
+                _ => format!("_m{}", member),
+            };
+            object.insert("name".to_owned(), json!(name));
+            object.insert("type".to_owned(), json!(self.json_type_name(base)?));
+            if base.class.opcode == spirv::Op::TypePointer {
+                object.insert("physical_pointer".to_owned(), json!(true));
+            }
+            array.insert_into(&mut object);
+
+            if let Some(decoration) =
+                self.find_member_decoration(struct_id, member, spirv::Decoration::Offset)?
+            {
+                object.insert(
+                    "offset".to_owned(),
+                    json!(get_operand_at!(decoration, Operand::LiteralBit32, 3)?),
+                );
+            }
+            if let Some(stride) =
+                self.get_decoration_literal(member_type_id, spirv::Decoration::ArrayStride)?
+            {
+                object.insert("array_stride".to_owned(), json!(stride));
+            }
+            if let Some(decoration) =
+                self.find_member_decoration(struct_id, member, spirv::Decoration::MatrixStride)?
+            {
+                object.insert(
+                    "matrix_stride".to_owned(),
+                    json!(get_operand_at!(decoration, Operand::LiteralBit32, 3)?),
+                );
+            }
+            if self
+                .find_member_decoration(struct_id, member, spirv::Decoration::RowMajor)?
+                .is_some()
+            {
+                object.insert("row_major".to_owned(), json!(true));
+            }
+            members.push(Value::Object(object));
+        }
+
+        Ok(json!({
+            "name": self.json_variable_name(struct_id)?,
+            "members": members,
+        }))
+    }
+
+    /// Describes a type that a physical storage buffer pointer points to, other than a struct
+    fn json_pointee(&self, pointee: &Instruction) -> Result<Value> {
+        let mut object = Map::new();
+        if pointee.class.opcode == spirv::Op::TypeArray
+            || pointee.class.opcode == spirv::Op::TypeRuntimeArray
+        {
+            let pointee_id = Self::result_id(pointee)?;
+            let (base, array) = self.json_peel_arrays(pointee)?;
+            let name = match base.class.opcode {
+                spirv::Op::TypeStruct => self.json_variable_name(Self::result_id(base)?)?,
+                _ => self.json_type_name(base)?,
+            };
+            object.insert("name".to_owned(), json!(name));
+            array.insert_into(&mut object);
+            object.insert(
+                "type".to_owned(),
+                json!(format!("_{}", get_operand_at!(pointee, Operand::IdRef, 0)?)),
+            );
+            object.insert(
+                "array_stride".to_owned(),
+                json!(self
+                    .get_decoration_literal(pointee_id, spirv::Decoration::ArrayStride)?
+                    .unwrap_or(0)),
+            );
+        } else {
+            object.insert("name".to_owned(), json!(self.json_type_name(pointee)?));
+            object.insert("members".to_owned(), json!([]));
+        }
+        Ok(Value::Object(object))
+    }
+
+    /// Returns the GLSL name of a type as used by SPIRV-Cross, or `_<id>` for structs
+    fn json_type_name(&self, type_instruction: &Instruction) -> Result<String> {
+        Ok(match type_instruction.class.opcode {
+            spirv::Op::TypeStruct => format!("_{}", Self::result_id(type_instruction)?),
+            // Physical storage buffer pointers refer to the struct they point to
+            spirv::Op::TypePointer => {
+                format!("_{}", get_operand_at!(type_instruction, Operand::IdRef, 1)?)
+            }
+            spirv::Op::TypeSampler => "sampler".to_owned(),
+            spirv::Op::TypeAccelerationStructureKHR => "accelerationStructureEXT".to_owned(),
+            spirv::Op::TypeImage => {
+                let image = self.get_image_type(type_instruction)?;
+                match (image.dim, image.sampled) {
+                    (spirv::Dim::DimSubpassData, _) => {
+                        Self::json_image_name("subpassInput", &image)
+                    }
+                    (_, Some(false)) => Self::json_image_name("image", &image),
+                    _ => Self::json_image_name("texture", &image),
+                }
+            }
+            spirv::Op::TypeSampledImage => {
+                let image_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
+                let image_instruction =
+                    self.find_referenced_type(type_instruction, image_type_id)?;
+                Self::json_image_name("sampler", &self.get_image_type(image_instruction)?)
+            }
+            _ => Self::json_numeric_name(&self.get_numeric_type(type_instruction)?),
+        })
+    }
+
+    fn json_numeric_name(ty: &NumericType) -> String {
+        let (scalar, prefix) = match ty.scalar {
+            ScalarType::Bool => ("bool", "b"),
+            ScalarType::Int {
+                width: 32,
+                signed: true,
+            } => ("int", "i"),
+            ScalarType::Int {
+                width: 32,
+                signed: false,
+            } => ("uint", "u"),
+            ScalarType::Int {
+                width: 8,
+                signed: true,
+            } => ("int8_t", "i8"),
+            ScalarType::Int {
+                width: 8,
+                signed: false,
+            } => ("uint8_t", "u8"),
+            ScalarType::Int {
+                width: 16,
+                signed: true,
+            } => ("int16_t", "i16"),
+            ScalarType::Int {
+                width: 16,
+                signed: false,
+            } => ("uint16_t", "u16"),
+            ScalarType::Int { signed: true, .. } => ("int64_t", "i64"),
+            ScalarType::Int { signed: false, .. } => ("uint64_t", "u64"),
+            ScalarType::Float { width: 16 } => ("float16_t", "f16"),
+            ScalarType::Float { width: 64 } => ("double", "d"),
+            ScalarType::Float { .. } => ("float", ""),
+        };
+
+        if ty.column_count > 1 {
+            if ty.column_count == ty.vector_size {
+                format!("{}mat{}", prefix, ty.column_count)
+            } else {
+                format!("{}mat{}x{}", prefix, ty.column_count, ty.vector_size)
+            }
+        } else if ty.vector_size > 1 {
+            format!("{}vec{}", prefix, ty.vector_size)
+        } else {
+            scalar.to_owned()
+        }
+    }
+
+    /// Returns the GLSL name of an image type, ie. `usampler2DArray` for `kind` `sampler`
+    fn json_image_name(kind: &str, image: &ImageType) -> String {
+        let prefix = match image.sampled_type {
+            ScalarType::Int {
+                width: 64,
+                signed: true,
+            } => "i64",
+            ScalarType::Int {
+                width: 64,
+                signed: false,
+            } => "u64",
+            ScalarType::Int { signed: true, .. } => "i",
+            ScalarType::Int { signed: false, .. } => "u",
+            _ => "",
+        };
+        let dim = match image.dim {
+            spirv::Dim::Dim1D => "1D",
+            spirv::Dim::Dim2D => "2D",
+            spirv::Dim::Dim3D => "3D",
+            spirv::Dim::DimCube => "Cube",
+            spirv::Dim::DimRect => "2DRect",
+            spirv::Dim::DimBuffer => "Buffer",
+            _ => "",
+        };
+        format!(
+            "{}{}{}{}{}{}",
+            prefix,
+            kind,
+            dim,
+            if image.multisampled { "MS" } else { "" },
+            if image.arrayed { "Array" } else { "" },
+            if kind == "sampler" && image.depth == Some(true) {
+                "Shadow"
+            } else {
+                ""
+            }
+        )
+    }
+
+    /// Returns the GLSL layout qualifier of the format of a storage image
+    fn json_format(image: &ImageType) -> &'static str {
+        use spirv::ImageFormat as F;
+        match image.format {
+            F::Rgba32f => "rgba32f",
+            F::Rgba16f => "rgba16f",
+            F::R32f => "r32f",
+            F::Rgba8 => "rgba8",
+            F::Rgba8Snorm => "rgba8_snorm",
+            F::Rg32f => "rg32f",
+            F::Rg16f => "rg16f",
+            F::R11fG11fB10f => "r11f_g11f_b10f",
+            F::R16f => "r16f",
+            F::Rgba16 => "rgba16",
+            F::Rgb10A2 => "rgb10_a2",
+            F::Rg16 => "rg16",
+            F::Rg8 => "rg8",
+            F::R16 => "r16",
+            F::R8 => "r8",
+            F::Rgba16Snorm => "rgba16_snorm",
+            F::Rg16Snorm => "rg16_snorm",
+            F::Rg8Snorm => "rg8_snorm",
+            F::R16Snorm => "r16_snorm",
+            F::R8Snorm => "r8_snorm",
+            F::Rgba32i => "rgba32i",
+            F::Rgba16i => "rgba16i",
+            F::Rgba8i => "rgba8i",
+            F::R32i => "r32i",
+            F::Rg32i => "rg32i",
+            F::Rg16i => "rg16i",
+            F::Rg8i => "rg8i",
+            F::R16i => "r16i",
+            F::R8i => "r8i",
+            F::Rgba32ui => "rgba32ui",
+            F::Rgba16ui => "rgba16ui",
+            F::Rgba8ui => "rgba8ui",
+            F::R32ui => "r32ui",
+            F::Rgb10a2ui => "rgb10_a2ui",
+            F::Rg32ui => "rg32ui",
+            F::Rg16ui => "rg16ui",
+            F::Rg8ui => "rg8ui",
+            F::R16ui => "r16ui",
+            F::R8ui => "r8ui",
+            F::R64ui => "r64ui",
+            F::R64i => "r64i",
+            _ => "unknown",
+        }
+    }
+
+    /// Returns the SPIRV-Cross shorthand of `execution_model`
+    fn json_mode(execution_model: spirv::ExecutionModel) -> &'static str {
+        use spirv::ExecutionModel as M;
+        match execution_model {
+            M::Vertex => "vert",
+            M::TessellationControl => "tesc",
+            M::TessellationEvaluation => "tese",
+            M::Geometry => "geom",
+            M::Fragment => "frag",
+            M::GLCompute => "comp",
+            M::TaskNV | M::TaskEXT => "task",
+            M::MeshNV | M::MeshEXT => "mesh",
+            M::RayGenerationNV => "rgen",
+            M::IntersectionNV => "rint",
+            M::AnyHitNV => "rahit",
+            M::ClosestHitNV => "rchit",
+            M::MissNV => "rmiss",
+            M::CallableNV => "rcall",
+            // Placeholder used by SPIRV-Cross for models it cannot name
+            M::Kernel => "???",
+        }
+    }
+}
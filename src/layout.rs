@@ -0,0 +1,281 @@
+//! Memory layout of structs in push constant and buffer blocks
+
+use crate::{ArrayLength, NumericType, ReflectError, Reflection, Result, ScalarType};
+use rspirv::dr::{Instruction, Operand};
+use rspirv::spirv;
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructLayout {
+    /// Debug name of the struct type
+    pub name: String,
+    /// Size in bytes up to the end of the last member.
+    ///
+    /// A trailing runtime array does not contribute to the size.
+    pub size: u32,
+    pub members: Vec<MemberLayout>,
+}
+
+impl StructLayout {
+    /// Returns the stride of the elements of a trailing runtime array, if the struct ends in one.
+    ///
+    /// A buffer holding `n` such elements needs to be `size + n * stride` bytes large.
+    pub fn runtime_array_stride(&self) -> Option<u32> {
+        match self.members.iter().max_by_key(|member| member.offset)?.ty {
+            TypeLayout::Array {
+                length: None,
+                stride,
+                ..
+            } => Some(stride),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemberLayout {
+    pub name: String,
+    /// Offset in bytes from the start of the containing struct
+    pub offset: u32,
+    /// Size in bytes, `0` for runtime arrays.  Arrays sized by specialization constants, or by
+    /// an `OpSpecConstantOp` computed from them, use their default values.
+    pub size: u32,
+    pub ty: TypeLayout,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TypeLayout {
+    /// A scalar or vector
+    Numeric(NumericType),
+    Matrix {
+        ty: NumericType,
+        /// Distance in bytes between two columns, or two rows if `row_major` is set
+        stride: u32,
+        row_major: bool,
+    },
+    Array {
+        element: Box<TypeLayout>,
+        /// Number of elements, or `None` for a runtime array
+        length: Option<ArrayLength>,
+        /// Distance in bytes between two elements
+        stride: u32,
+    },
+    /// A nested struct, shared between all members of the same struct type
+    Struct(Arc<StructLayout>),
+    /// A `PhysicalStorageBuffer` pointer, as used by `GL_EXT_buffer_reference`
+    PhysicalPointer,
+}
+
+/// `MatrixStride` and `RowMajor` decorations of a struct member, which apply to any matrix
+/// nested within arrays in that member
+#[derive(Clone, Copy, Default)]
+struct MatrixDecorations {
+    stride: Option<u32>,
+    row_major: bool,
+}
+
+impl Reflection {
+    /// Returns the layout of an `OpTypeStruct` `Instruction` based on its `Offset`,
+    /// `ArrayStride` and `MatrixStride` decorations
+    pub(crate) fn get_struct_layout(
+        &self,
+        struct_instruction: &Instruction,
+    ) -> Result<StructLayout> {
+        let layout = self.get_cached_struct_layout(struct_instruction, &mut BTreeMap::new())?;
+        Ok(Arc::try_unwrap(layout).unwrap_or_else(|layout| (*layout).clone()))
+    }
+
+    /// Same as [`Self::get_struct_layout()`], but reuses the layouts of nested structs in
+    /// `cache`, keyed by their type id.  Structs are commonly nested many times over, which
+    /// would otherwise make the time and memory spent exponential in the nesting depth.
+    fn get_cached_struct_layout(
+        &self,
+        struct_instruction: &Instruction,
+        cache: &mut BTreeMap<u32, Arc<StructLayout>>,
+    ) -> Result<Arc<StructLayout>> {
+        let struct_id = struct_instruction
+            .result_id
+            .ok_or_else(|| ReflectError::MissingResultId(struct_instruction.clone()))?;
+        if let Some(layout) = cache.get(&struct_id) {
+            return Ok(Arc::clone(layout));
+        }
+
+        let mut members = vec![];
+        for idx in 0..struct_instruction.operands.len() {
+            let member_type_id = get_operand_at!(struct_instruction, Operand::IdRef, idx)?;
+            let member = idx.try_into()?;
+
+            let offset =
+                match self.find_member_decoration(struct_id, member, spirv::Decoration::Offset)? {
+                    Some(decoration) => get_operand_at!(decoration, Operand::LiteralBit32, 3)?,
+                    None => {
+                        return Err(ReflectError::MissingOffsetDecoration(
+                            struct_instruction.clone(),
+                            member,
+                        ))
+                    }
+                };
+            let matrix = MatrixDecorations {
+                stride: match self.find_member_decoration(
+                    struct_id,
+                    member,
+                    spirv::Decoration::MatrixStride,
+                )? {
+                    Some(decoration) => {
+                        Some(get_operand_at!(decoration, Operand::LiteralBit32, 3)?)
+                    }
+                    None => None,
+                },
+                row_major: self
+                    .find_member_decoration(struct_id, member, spirv::Decoration::RowMajor)?
+                    .is_some(),
+            };
+
+            let member_type = self.find_referenced_type(struct_instruction, member_type_id)?;
+            let (ty, size) = self.get_type_layout(member_type, matrix, cache)?;
+
+            members.push(MemberLayout {
+                name: self
+                    .get_member_name(struct_id, member)?
+                    .unwrap_or_default()
+                    .to_owned(),
+                offset,
+                size,
+                ty,
+            });
+        }
+
+        let mut size = 0;
+        for member in &members {
+            let end = member
+                .offset
+                .checked_add(member.size)
+                .ok_or_else(|| ReflectError::SizeOverflow(struct_instruction.clone()))?;
+            size = size.max(end);
+        }
+
+        let layout = Arc::new(StructLayout {
+            name: self.get_name(struct_id)?.unwrap_or_default().to_owned(),
+            size,
+            members,
+        });
+        cache.insert(struct_id, Arc::clone(&layout));
+        Ok(layout)
+    }
+
+    /// Returns the layout and size in bytes of an `OpType*` `Instruction`
+    fn get_type_layout(
+        &self,
+        type_instruction: &Instruction,
+        matrix: MatrixDecorations,
+        cache: &mut BTreeMap<u32, Arc<StructLayout>>,
+    ) -> Result<(TypeLayout, u32)> {
+        let overflow = || ReflectError::SizeOverflow(type_instruction.clone());
+
+        match type_instruction.class.opcode {
+            spirv::Op::TypeInt | spirv::Op::TypeFloat | spirv::Op::TypeVector => {
+                let ty = self.get_numeric_type(type_instruction)?;
+                let size = Self::get_scalar_size(type_instruction, ty.scalar)?
+                    .checked_mul(ty.vector_size)
+                    .ok_or_else(overflow)?;
+                Ok((TypeLayout::Numeric(ty), size))
+            }
+            spirv::Op::TypeMatrix => {
+                let ty = self.get_numeric_type(type_instruction)?;
+                let scalar_size = Self::get_scalar_size(type_instruction, ty.scalar)?;
+                // Without explicit stride, assume tightly packed columns or rows
+                let (major_count, minor_count) = if matrix.row_major {
+                    (ty.vector_size, ty.column_count)
+                } else {
+                    (ty.column_count, ty.vector_size)
+                };
+                let stride = match matrix.stride {
+                    Some(stride) => stride,
+                    None => scalar_size.checked_mul(minor_count).ok_or_else(overflow)?,
+                };
+                Ok((
+                    TypeLayout::Matrix {
+                        ty,
+                        stride,
+                        row_major: matrix.row_major,
+                    },
+                    stride.checked_mul(major_count).ok_or_else(overflow)?,
+                ))
+            }
+            spirv::Op::TypeArray | spirv::Op::TypeRuntimeArray => {
+                let type_id = type_instruction
+                    .result_id
+                    .ok_or_else(|| ReflectError::MissingResultId(type_instruction.clone()))?;
+                let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
+                let element_type = self.find_referenced_type(type_instruction, element_type_id)?;
+                let (element, element_size) = self.get_type_layout(element_type, matrix, cache)?;
+                let stride = self
+                    .get_decoration_literal(type_id, spirv::Decoration::ArrayStride)?
+                    .unwrap_or(element_size);
+                let length = if type_instruction.class.opcode == spirv::Op::TypeArray {
+                    Some(self.get_array_length_kind(type_instruction)?)
+                } else {
+                    None
+                };
+                // Runtime arrays do not contribute to the size
+                let count = match length {
+                    Some(length) => length.default_length().ok_or_else(|| {
+                        ReflectError::UnevaluatedArrayLength(type_instruction.clone())
+                    })?,
+                    None => 0,
+                };
+                let size = count.checked_mul(stride).ok_or_else(overflow)?;
+                Ok((
+                    TypeLayout::Array {
+                        element: Box::new(element),
+                        length,
+                        stride,
+                    },
+                    size,
+                ))
+            }
+            spirv::Op::TypeStruct => {
+                let layout = self.get_cached_struct_layout(type_instruction, cache)?;
+                let size = layout.size;
+                Ok((TypeLayout::Struct(layout), size))
+            }
+            spirv::Op::TypePointer => {
+                let memory_model = self
+                    .0
+                    .memory_model
+                    .as_ref()
+                    .ok_or(ReflectError::MissingMemoryModel)?;
+                let addressing_model = get_operand_at!(memory_model, Operand::AddressingModel, 0)?;
+
+                let storage_class = get_operand_at!(type_instruction, Operand::StorageClass, 0)?;
+
+                // https://registry.khronos.org/SPIR-V/specs/unified1/SPIRV.html#Addressing_Model
+                // https://registry.khronos.org/SPIR-V/specs/unified1/SPIRV.html#Storage_Class
+                match (addressing_model, storage_class) {
+                    (
+                        // https://github.com/KhronosGroup/SPIRV-Registry/blob/main/extensions/KHR/SPV_KHR_physical_storage_buffer.asciidoc
+                        spirv::AddressingModel::PhysicalStorageBuffer64,
+                        spirv::StorageClass::PhysicalStorageBuffer,
+                    ) => Ok((TypeLayout::PhysicalPointer, 8)),
+                    (a, s) => Err(ReflectError::InvalidAddressingModelAndStorageClass(a, s)),
+                }
+            }
+            _ => Err(ReflectError::UnhandledTypeInstruction(
+                type_instruction.clone(),
+            )),
+        }
+    }
+
+    /// Returns the size in bytes of `scalar`, booleans have no defined size in memory
+    fn get_scalar_size(type_instruction: &Instruction, scalar: ScalarType) -> Result<u32> {
+        scalar
+            .width()
+            .map(|width| width / 8)
+            .ok_or_else(|| ReflectError::UnhandledTypeInstruction(type_instruction.clone()))
+    }
+}
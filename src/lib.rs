@@ -43,8 +43,12 @@ pub enum ReflectError {
     UnhandledTypeInstruction(Instruction),
     #[error("{0:?} does not generate a result")]
     MissingResultId(Instruction),
+    #[error("{0:?} lacks a result type")]
+    MissingResultType(Instruction),
     #[error("No instruction assigns to {0:?}")]
     UnassignedResultId(u32),
+    #[error("Member {1} of {0:?} lacks an offset decoration")]
+    MissingOffsetDecoration(Instruction, u32),
     #[error("rspirv reflect lacks module header")]
     MissingHeader,
     #[error("rspirv reflect lacks `OpMemoryModel`")]
@@ -57,12 +61,60 @@ pub enum ReflectError {
     ParseError(#[from] rspirv::binary::ParseState),
     #[error("OpTypeInt cannot have width {0}")]
     UnexpectedIntWidth(u32),
+    #[error("OpTypeFloat cannot have width {0}")]
+    UnexpectedFloatWidth(u32),
     #[error(
         "Invalid or unimplemented combination of AddressingModel {0:?} and StorageClass {1:?}"
     )]
     InvalidAddressingModelAndStorageClass(spirv::AddressingModel, spirv::StorageClass),
     #[error(transparent)]
     TryFromIntError(#[from] TryFromIntError),
+    #[error("Query is not supported for entry points with execution model {0:?}")]
+    UnexpectedExecutionModel(spirv::ExecutionModel),
+    #[error("Interface variable `{0}` lacks a location decoration")]
+    MissingLocationDecoration(String),
+    #[error("{0:?} cannot be used as vertex input attribute")]
+    UnsupportedVertexInputType(NumericType),
+    #[error("Binding {1} in set {0} is declared as both {2:?} and {3:?}")]
+    ConflictingDescriptorType(u32, u32, DescriptorType, DescriptorType),
+    #[error("Binding {1} in set {0} is declared with both {2:?} and {3:?} descriptors")]
+    ConflictingBindingCount(u32, u32, BindingCount, BindingCount),
+    #[error("{} bindings are declared differently by multiple stages", .0.len())]
+    ConflictingBindings(Vec<ReflectError>),
+    #[error("Multiple descriptors are remapped to binding {1} in set {0}")]
+    RemappedBindingCollision(u32, u32),
+    #[error("Instruction at word {0} extends past the end of the module")]
+    TruncatedInstruction(usize),
+    #[error("OpSpecConstantOp at word {0} wraps opcode {1}, which is not allowed")]
+    InvalidSpecConstantOp(usize, u32),
+    #[error("{0:?} refers to type {1} which is not declared before it")]
+    ForwardTypeReference(Instruction, u32),
+    #[error("{0:?} declares an array without elements")]
+    ZeroLengthArray(Instruction),
+    #[error("Length of {0:?} is computed by an OpSpecConstantOp that cannot be evaluated")]
+    UnevaluatedArrayLength(Instruction),
+    #[error("{0:?} does not point to storage class {1:?}")]
+    StorageClassMismatch(Instruction, spirv::StorageClass),
+    #[error("{0:?} cannot be combined with a sampler")]
+    UnexpectedSampledImage(Instruction),
+    #[error("BufferBlock decoration is obsolete in SPIR-V > 1.3: {0:?}")]
+    ObsoleteBufferBlock(Instruction),
+    #[error("{0:?} has multiple {1:?} decorations")]
+    DuplicateDecoration(Instruction, spirv::Decoration),
+    #[error("Multiple descriptors are bound to binding {1} in set {0}")]
+    DuplicateBinding(u32, u32),
+    #[error("Size of {0:?} does not fit in 32 bits")]
+    SizeOverflow(Instruction),
+    #[error("Locations of interface variable `{0}` do not fit in 32 bits")]
+    LocationOverflow(String),
+    #[error("Vertex input `{0}` extends past location {1}")]
+    TooManyVertexInputLocations(String, u32),
+    #[error("Interface block `{0}` flattens into more than {1} variables")]
+    TooManyInterfaceMembers(String, usize),
+    #[error("Binding {0} cannot be expressed in wgpu: unsupported {1}")]
+    UnsupportedWgpuBinding(u32, String),
+    #[error("Layout of `{0}` cannot be represented as a Rust struct: {1}")]
+    UnrepresentableLayout(String, String),
 }
 
 type Result<V, E = ReflectError> = ::std::result::Result<V, E>;
@@ -70,6 +122,11 @@ type Result<V, E = ReflectError> = ::std::result::Result<V, E>;
 /// These are bit-exact with ash and the Vulkan specification,
 /// they're mirrored here to prevent a dependency on ash
 #[derive(Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 #[repr(transparent)]
 pub struct DescriptorType(pub u32);
 
@@ -115,6 +172,7 @@ impl std::fmt::Debug for DescriptorType {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BindingCount {
     /// A single resource binding.
     ///
@@ -142,16 +200,71 @@ pub enum BindingCount {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DescriptorInfo {
     pub ty: DescriptorType,
     pub binding_count: BindingCount,
     pub name: String,
+    /// Whether the descriptor is referenced by any function reachable from the entry point (or
+    /// any entry point when reflecting the whole module).
+    ///
+    /// Descriptors that are declared but never used, for example because they come from a shared
+    /// include file, do not need to be part of the pipeline layout.
+    pub is_statically_used: bool,
+    /// Layout of the block backing a uniform or storage buffer, `None` for all other descriptor
+    /// types
+    pub block: Option<StructLayout>,
+    /// Properties of the image backing a sampled, storage or input attachment image, combined
+    /// image sampler or texel buffer, `None` for all other descriptor types
+    pub image: Option<ImageType>,
+    /// Access permitted by the `NonReadable` and `NonWritable` decorations on the variable, or on
+    /// every member of its buffer block (ie. `readonly`/`writeonly` in GLSL and
+    /// `StructuredBuffer`/`ByteAddressBuffer` in HLSL).
+    ///
+    /// Descriptor types that shaders cannot write to are always [`AccessFlags::READ`].
+    pub access: AccessFlags,
+    /// Access performed by the loads, stores, image and atomic instructions reachable from the
+    /// entry point (or any entry point when reflecting the whole module).
+    ///
+    /// This can be narrower than [`Self::access`], for example for an HLSL `RWStructuredBuffer`
+    /// that is only ever read from.
+    pub used_access: AccessFlags,
+    /// How the image sampling instructions reachable from the entry point (or any entry point
+    /// when reflecting the whole module) use this sampler, sampled image or combined image
+    /// sampler.
+    ///
+    /// A sampler with [`SamplingFlags::COMPARE`] must be created as a comparison sampler, and an
+    /// image with [`SamplingFlags::COMPARE`] is depth-sampled rather than filtered.  Always
+    /// [`SamplingFlags::NONE`] for all other descriptor types.
+    pub sampling: SamplingFlags,
+    /// Stages of the entry point (or all entry points when reflecting the whole module) that
+    /// statically use this descriptor
+    pub stages: ShaderStageFlags,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntryPoint {
+    /// Name of the entry point as passed to `vkCreate*Pipelines`
+    pub name: String,
+    pub execution_model: spirv::ExecutionModel,
+    /// Result id of the `OpFunction` implementing this entry point
+    pub id: u32,
+    /// Ids of the global `OpVariable`s listed in the `OpEntryPoint` interface.
+    ///
+    /// Before SPIR-V 1.4 this only contains `Input` and `Output` variables, from 1.4 onwards it
+    /// contains every global variable statically used by the entry point.
+    pub interface: Vec<u32>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PushConstantInfo {
     pub offset: u32,
     pub size: u32,
+    /// Stages of the entry point (or all entry points when reflecting the whole module) that
+    /// statically use the push constant block
+    pub stages: ShaderStageFlags,
 }
 
 macro_rules! get_ref_operand_at {
@@ -182,12 +295,132 @@ macro_rules! get_operand_at {
     };
 }
 
+/// Implements bitwise operators, `is_empty()`, `contains()` and a `Debug` representation listing
+/// the set flags by name for a `u32` newtype holding a bitmask
+macro_rules! impl_flags {
+    ($ty:ident, [$($flag:ident),* $(,)?]) => {
+        impl $ty {
+            pub fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            /// Returns whether all bits set in `other` are also set in `self`
+            pub fn contains(self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+        }
+
+        impl std::ops::BitOr for $ty {
+            type Output = Self;
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign for $ty {
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0
+            }
+        }
+
+        impl std::ops::BitAnd for $ty {
+            type Output = Self;
+            fn bitand(self, rhs: Self) -> Self {
+                Self(self.0 & rhs.0)
+            }
+        }
+
+        impl std::ops::BitAndAssign for $ty {
+            fn bitand_assign(&mut self, rhs: Self) {
+                self.0 &= rhs.0
+            }
+        }
+
+        impl std::ops::Not for $ty {
+            type Output = Self;
+            fn not(self) -> Self {
+                Self(!self.0)
+            }
+        }
+
+        impl std::fmt::Debug for $ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                if self.is_empty() {
+                    return f.write_str("NONE");
+                }
+
+                let mut remaining = *self;
+                let mut first = true;
+                $(
+                    if self.contains(Self::$flag) {
+                        if !first {
+                            f.write_str(" | ")?;
+                        }
+                        f.write_str(stringify!($flag))?;
+                        remaining &= !Self::$flag;
+                        first = false;
+                    }
+                )*
+                if !remaining.is_empty() {
+                    if !first {
+                        f.write_str(" | ")?;
+                    }
+                    write!(f, "{:#x}", remaining.0)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+mod access;
+mod analysis;
+mod capabilities;
+mod codegen;
+mod format;
+mod interface;
+#[cfg(feature = "json")]
+mod json;
+mod layout;
+mod matching;
+mod packing;
+mod pipeline;
+mod remap;
+mod sampling;
+mod specialization;
+mod stage;
+mod types;
+mod vertex;
+#[cfg(feature = "ash")]
+mod vk;
+#[cfg(feature = "wgpu")]
+mod wgpu;
+
+pub use access::*;
+pub use capabilities::*;
+pub use format::*;
+pub use interface::*;
+pub use layout::*;
+pub use matching::*;
+pub use packing::*;
+pub use pipeline::*;
+pub use sampling::*;
+pub use specialization::*;
+pub use stage::*;
+pub use types::*;
+pub use vertex::*;
+#[cfg(feature = "ash")]
+pub use vk::*;
+#[cfg(feature = "wgpu")]
+pub use wgpu::*;
+
 impl Reflection {
     pub fn new(module: Module) -> Self {
         Self(module)
     }
 
     pub fn new_from_spirv(code: &[u8]) -> Result<Self> {
+        Self::check_instructions(code)?;
         Ok(Self::new({
             let mut loader = Loader::new();
             let p = Parser::new(code, &mut loader);
@@ -196,6 +429,110 @@ impl Reflection {
         }))
     }
 
+    /// Rejects instructions whose word count runs past the end of `code`, and `OpSpecConstantOp`
+    /// instructions wrapping an opcode the specification does not allow. The `rspirv` parser
+    /// does not catch the former when decoding string operands, and panics on the latter when
+    /// the wrapped opcode has result or context-dependent operands
+    fn check_instructions(code: &[u8]) -> Result<()> {
+        const HEADER_WORDS: usize = 5;
+        let words = code
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect::<Vec<_>>();
+        if words.len() < HEADER_WORDS || words[0] != spirv::MAGIC_NUMBER {
+            // Not a SPIR-V module, reported by the parser
+            return Ok(());
+        }
+
+        let mut offset = HEADER_WORDS;
+        while let Some(word) = words.get(offset) {
+            let word_count = (word >> 16) as usize;
+            if word_count == 0 {
+                // Reported by the parser
+                break;
+            }
+            if offset + word_count > words.len() {
+                return Err(ReflectError::TruncatedInstruction(offset));
+            }
+            if word & 0xffff == spirv::Op::SpecConstantOp as u32 && word_count > 3 {
+                let opcode = words[offset + 3];
+                if !spirv::Op::from_u32(opcode).is_some_and(Self::is_spec_constant_opcode) {
+                    return Err(ReflectError::InvalidSpecConstantOp(offset, opcode));
+                }
+            }
+            offset += word_count;
+        }
+        Ok(())
+    }
+
+    /// Whether `opcode` may be wrapped by `OpSpecConstantOp`, for either the `Shader` or `Kernel`
+    /// capability
+    fn is_spec_constant_opcode(opcode: spirv::Op) -> bool {
+        use spirv::Op::*;
+        matches!(
+            opcode,
+            SConvert
+                | UConvert
+                | FConvert
+                | SNegate
+                | Not
+                | IAdd
+                | ISub
+                | IMul
+                | UDiv
+                | SDiv
+                | UMod
+                | SRem
+                | SMod
+                | ShiftRightLogical
+                | ShiftRightArithmetic
+                | ShiftLeftLogical
+                | BitwiseOr
+                | BitwiseXor
+                | BitwiseAnd
+                | VectorShuffle
+                | CompositeExtract
+                | CompositeInsert
+                | LogicalOr
+                | LogicalAnd
+                | LogicalNot
+                | LogicalEqual
+                | LogicalNotEqual
+                | Select
+                | IEqual
+                | INotEqual
+                | ULessThan
+                | SLessThan
+                | UGreaterThan
+                | SGreaterThan
+                | ULessThanEqual
+                | SLessThanEqual
+                | UGreaterThanEqual
+                | SGreaterThanEqual
+                | QuantizeToF16
+                | ConvertFToS
+                | ConvertSToF
+                | ConvertFToU
+                | ConvertUToF
+                | ConvertPtrToU
+                | ConvertUToPtr
+                | GenericCastToPtr
+                | PtrCastToGeneric
+                | Bitcast
+                | FNegate
+                | FAdd
+                | FSub
+                | FMul
+                | FDiv
+                | FRem
+                | FMod
+                | AccessChain
+                | InBoundsAccessChain
+                | PtrAccessChain
+                | InBoundsPtrAccessChain
+        )
+    }
+
     /// Returns all instructions where the first operand (`Instruction::operands[0]`) equals `IdRef(id)`
     pub fn find_annotations_for_id(
         annotations: &[Instruction],
@@ -223,10 +560,132 @@ impl Reflection {
             .ok_or(ReflectError::UnassignedResultId(id))
     }
 
+    /// Returns a mapping from result ids to their `OpName` debug name
+    fn get_names(&self) -> Result<BTreeMap<u32, String>> {
+        self.0
+            .debug_names
+            .iter()
+            .filter(|i| i.class.opcode == spirv::Op::Name)
+            .map(|i| -> Result<(u32, String)> {
+                let element_type_id = get_operand_at!(i, Operand::IdRef, 0)?;
+                let name = get_ref_operand_at!(i, Operand::LiteralString, 1)?;
+                Ok((element_type_id, name.clone()))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+    }
+
+    /// Returns the `OpName` debug name of `id`
+    fn get_name(&self, id: u32) -> Result<Option<&str>> {
+        for i in &self.0.debug_names {
+            if i.class.opcode == spirv::Op::Name && get_operand_at!(i, Operand::IdRef, 0)? == id {
+                return Ok(Some(get_ref_operand_at!(i, Operand::LiteralString, 1)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the `OpMemberName` debug name of member `member` of struct `id`
+    fn get_member_name(&self, id: u32, member: u32) -> Result<Option<&str>> {
+        for i in &self.0.debug_names {
+            if i.class.opcode == spirv::Op::MemberName
+                && get_operand_at!(i, Operand::IdRef, 0)? == id
+                && get_operand_at!(i, Operand::LiteralBit32, 1)? == member
+            {
+                return Ok(Some(get_ref_operand_at!(i, Operand::LiteralString, 2)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the first `OpDecorate` instruction applying `decoration` to `id`
+    fn find_decoration(
+        &self,
+        id: u32,
+        decoration: spirv::Decoration,
+    ) -> Result<Option<&Instruction>> {
+        for i in Self::find_annotations_for_id(&self.0.annotations, id)? {
+            if i.class.opcode == spirv::Op::Decorate
+                && get_operand_at!(i, Operand::Decoration, 1)? == decoration
+            {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the literal value of `decoration` on `id`, if it is decorated with it
+    fn get_decoration_literal(
+        &self,
+        id: u32,
+        decoration: spirv::Decoration,
+    ) -> Result<Option<u32>> {
+        match self.find_decoration(id, decoration)? {
+            Some(decoration) => Ok(Some(get_operand_at!(decoration, Operand::LiteralBit32, 2)?)),
+            None => Ok(None),
+        }
+    }
+    /// Returns the first `OpMemberDecorate` instruction applying `decoration` to member `member` of
+    /// struct `id`
+    fn find_member_decoration(
+        &self,
+        id: u32,
+        member: u32,
+        decoration: spirv::Decoration,
+    ) -> Result<Option<&Instruction>> {
+        for i in Self::find_annotations_for_id(&self.0.annotations, id)? {
+            if i.class.opcode == spirv::Op::MemberDecorate
+                && get_operand_at!(i, Operand::LiteralBit32, 1)? == member
+                && get_operand_at!(i, Operand::Decoration, 2)? == decoration
+            {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns all entry points declared in the module, in declaration order
+    pub fn get_entry_points(&self) -> Result<Vec<EntryPoint>> {
+        self.0
+            .entry_points
+            .iter()
+            .map(|i| {
+                let execution_model = get_operand_at!(i, Operand::ExecutionModel, 0)?;
+                let id = get_operand_at!(i, Operand::IdRef, 1)?;
+                let name = get_ref_operand_at!(i, Operand::LiteralString, 2)?.clone();
+                let interface = (3..i.operands.len())
+                    .map(|idx| get_operand_at!(i, Operand::IdRef, idx))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(EntryPoint {
+                    name,
+                    execution_model,
+                    id,
+                    interface,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the workgroup size of the first entry point declaring one
     pub fn get_compute_group_size(&self) -> Option<(u32, u32, u32)> {
+        self.compute_group_size(None)
+    }
+
+    /// Returns the workgroup size declared for `entry_point`
+    pub fn get_compute_group_size_for_entry_point(
+        &self,
+        entry_point: &EntryPoint,
+    ) -> Option<(u32, u32, u32)> {
+        self.compute_group_size(Some(entry_point))
+    }
+
+    fn compute_group_size(&self, entry_point: Option<&EntryPoint>) -> Option<(u32, u32, u32)> {
         for inst in self.0.global_inst_iter() {
             if inst.class.opcode == spirv::Op::ExecutionMode {
-                use rspirv::dr::Operand::{ExecutionMode, LiteralBit32};
+                use rspirv::dr::Operand::{ExecutionMode, IdRef, LiteralBit32};
+                match (entry_point, inst.operands.first()) {
+                    (Some(entry_point), Some(IdRef(id))) if *id != entry_point.id => continue,
+                    _ => {}
+                }
                 if let [ExecutionMode(
                     spirv::ExecutionMode::LocalSize | spirv::ExecutionMode::LocalSizeHint,
                 ), LiteralBit32(x), LiteralBit32(y), LiteralBit32(z)] = inst.operands[1..]
@@ -240,6 +699,31 @@ impl Reflection {
         None
     }
 
+    /// Returns `true` if the global variable `var_id` can be used by `entry_point`.
+    ///
+    /// SPIR-V 1.4 and up list every global variable used by an entry point in its interface,
+    /// older versions only list `Input` and `Output` variables so all other variables are
+    /// considered to be visible to every entry point.
+    fn is_visible_to_entry_point(
+        &self,
+        var_id: u32,
+        entry_point: Option<&EntryPoint>,
+    ) -> Result<bool> {
+        let entry_point = match entry_point {
+            Some(entry_point) => entry_point,
+            None => return Ok(true),
+        };
+
+        let version = self
+            .0
+            .header
+            .as_ref()
+            .ok_or(ReflectError::MissingHeader)?
+            .version();
+
+        Ok(version < (1, 4) || entry_point.interface.contains(&var_id))
+    }
+
     /// Returns the descriptor type for a given variable `type_id`
     fn get_descriptor_type_for_var(
         &self,
@@ -264,58 +748,56 @@ impl Reflection {
         match type_instruction.class.opcode {
             spirv::Op::TypeArray => {
                 let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
-                let num_elements_id = get_operand_at!(type_instruction, Operand::IdRef, 1)?;
-                let num_elements =
-                    Self::find_assignment_for(&self.0.types_global_values, num_elements_id)?;
-                assert_eq!(num_elements.class.opcode, spirv::Op::Constant);
-                let num_elements_ty = Self::find_assignment_for(
-                    &self.0.types_global_values,
-                    num_elements.result_type.unwrap(),
-                )?;
-                // Array size can be any width, any signedness
-                assert_eq!(num_elements_ty.class.opcode, spirv::Op::TypeInt);
-                let num_elements = match get_operand_at!(num_elements_ty, Operand::LiteralBit32, 0)?
-                {
-                    32 => get_operand_at!(num_elements, Operand::LiteralBit32, 0)?.try_into()?,
-                    64 => get_operand_at!(num_elements, Operand::LiteralBit64, 0)?.try_into()?,
-                    x => return Err(ReflectError::UnexpectedIntWidth(x)),
-                };
-                assert!(num_elements >= 1);
+                let element_type = self.find_referenced_type(type_instruction, element_type_id)?;
+                let num_elements = self.get_array_length(type_instruction)?.try_into()?;
                 return Ok(DescriptorInfo {
                     binding_count: BindingCount::StaticSized(num_elements),
-                    ..self.get_descriptor_type_for_var(element_type_id, storage_class)?
+                    ..self.get_descriptor_type(element_type, storage_class)?
                 });
             }
             spirv::Op::TypeRuntimeArray => {
                 let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
+                let element_type = self.find_referenced_type(type_instruction, element_type_id)?;
                 return Ok(DescriptorInfo {
                     binding_count: BindingCount::Unbounded,
-                    ..self.get_descriptor_type_for_var(element_type_id, storage_class)?
+                    ..self.get_descriptor_type(element_type, storage_class)?
                 });
             }
             spirv::Op::TypePointer => {
                 let ptr_storage_class =
                     get_operand_at!(type_instruction, Operand::StorageClass, 0)?;
                 let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 1)?;
-                assert_eq!(storage_class, ptr_storage_class);
-                return self.get_descriptor_type_for_var(element_type_id, storage_class);
+                if storage_class != ptr_storage_class {
+                    return Err(ReflectError::StorageClassMismatch(
+                        type_instruction.clone(),
+                        storage_class,
+                    ));
+                }
+                let element_type = self.find_referenced_type(type_instruction, element_type_id)?;
+                return self.get_descriptor_type(element_type, storage_class);
             }
             spirv::Op::TypeSampledImage => {
                 let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
 
                 let image_instruction =
-                    Self::find_assignment_for(&self.0.types_global_values, element_type_id)?;
+                    self.find_referenced_type(type_instruction, element_type_id)?;
 
                 let descriptor = self.get_descriptor_type(image_instruction, storage_class)?;
 
                 let dim = get_operand_at!(image_instruction, Operand::Dim, 1)?;
-                assert_ne!(dim, spirv::Dim::DimSubpassData);
+                if dim == spirv::Dim::DimSubpassData {
+                    return Err(ReflectError::UnexpectedSampledImage(
+                        image_instruction.clone(),
+                    ));
+                }
 
                 return Ok(if dim == spirv::Dim::DimBuffer {
                     if descriptor.ty != DescriptorType::UNIFORM_TEXEL_BUFFER
                         && descriptor.ty != DescriptorType::STORAGE_TEXEL_BUFFER
                     {
-                        todo!("Unexpected sampled image type {:?}", descriptor.ty)
+                        return Err(ReflectError::UnexpectedSampledImage(
+                            image_instruction.clone(),
+                        ));
                     }
                     descriptor
                 } else {
@@ -391,14 +873,12 @@ impl Reflection {
                     DescriptorType::STORAGE_BUFFER
                 } else if version >= (1, 3) {
                     // From 1.3, StorageClass is supported.
-                    assert!(
-                        !is_storage_buffer,
-                        "BufferBlock decoration is obsolete in SPIRV > 1.3"
-                    );
-                    assert!(
-                        is_uniform_buffer,
-                        "Struct requires Block annotation in SPIRV > 1.3"
-                    );
+                    if is_storage_buffer {
+                        return Err(ReflectError::ObsoleteBufferBlock(type_instruction.clone()));
+                    }
+                    if !is_uniform_buffer {
+                        return Err(ReflectError::UnknownStruct(type_instruction.clone()));
+                    }
                     match storage_class {
                         spirv::StorageClass::Uniform | spirv::StorageClass::UniformConstant => {
                             DescriptorType::UNIFORM_BUFFER
@@ -421,16 +901,56 @@ impl Reflection {
             }
         };
 
+        // Only uniform and storage buffers are backed by a struct
+        let block = if type_instruction.class.opcode == spirv::Op::TypeStruct {
+            Some(self.get_struct_layout(type_instruction)?)
+        } else {
+            None
+        };
+        let image = if type_instruction.class.opcode == spirv::Op::TypeImage {
+            Some(self.get_image_type(type_instruction)?)
+        } else {
+            None
+        };
+
         Ok(DescriptorInfo {
             ty: descriptor_type,
             binding_count: BindingCount::One,
             name: "".to_string(),
+            is_statically_used: false,
+            block,
+            image,
+            access: AccessFlags::NONE,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE,
         })
     }
 
     /// Returns a nested mapping, where the first level maps descriptor set indices (register spaces)
     /// and the second level maps descriptor binding indices (registers) to descriptor information.
     pub fn get_descriptor_sets(&self) -> Result<BTreeMap<u32, BTreeMap<u32, DescriptorInfo>>> {
+        self.descriptor_sets(None)
+    }
+
+    /// Same as [`Self::get_descriptor_sets()`], but only returns descriptors that are visible to
+    /// `entry_point`.
+    ///
+    /// Modules older than SPIR-V 1.4 do not list descriptors in the `OpEntryPoint` interface,
+    /// in which case all descriptors declared in the module are returned.  Use
+    /// [`DescriptorInfo::is_statically_used`] to find out which of those are used by
+    /// `entry_point`.
+    pub fn get_descriptor_sets_for_entry_point(
+        &self,
+        entry_point: &EntryPoint,
+    ) -> Result<BTreeMap<u32, BTreeMap<u32, DescriptorInfo>>> {
+        self.descriptor_sets(Some(entry_point))
+    }
+
+    fn descriptor_sets(
+        &self,
+        entry_point: Option<&EntryPoint>,
+    ) -> Result<BTreeMap<u32, BTreeMap<u32, DescriptorInfo>>> {
         let mut unique_sets = BTreeMap::new();
         let reflect = &self.0;
 
@@ -454,40 +974,54 @@ impl Reflection {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        let names = reflect
-            .debug_names
-            .iter()
-            .filter(|i| i.class.opcode == spirv::Op::Name)
-            .map(|i| -> Result<(u32, String)> {
-                let element_type_id = get_operand_at!(i, Operand::IdRef, 0)?;
-                let name = get_ref_operand_at!(i, Operand::LiteralString, 1)?;
-                Ok((element_type_id, name.clone()))
-            })
-            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        let names = self.get_names()?;
+
+        let entry_points = match entry_point {
+            Some(entry_point) => vec![entry_point.clone()],
+            None => self.get_entry_points()?,
+        };
+        let mut variable_stages = BTreeMap::<u32, ShaderStageFlags>::new();
+        let mut variable_accesses = BTreeMap::<u32, AccessFlags>::new();
+        let mut variable_sampling = BTreeMap::<u32, SamplingFlags>::new();
+        for entry_point in &entry_points {
+            let stage = Self::entry_point_stage(entry_point);
+            for var_id in self.statically_used_variables(entry_point)? {
+                *variable_stages.entry(var_id).or_default() |= stage;
+            }
+            let analysis = self.analyze_functions(entry_point)?;
+            for (var_id, access) in analysis.variable_accesses()? {
+                *variable_accesses.entry(var_id).or_default() |= access;
+            }
+            for (var_id, sampling) in analysis.variable_sampling()? {
+                *variable_sampling.entry(var_id).or_default() |= sampling;
+            }
+        }
 
         for var in uniform_variables {
             if let Some(var_id) = var.result_id {
+                if !self.is_visible_to_entry_point(var_id, entry_point)? {
+                    continue;
+                }
+
                 let annotations =
                     Reflection::find_annotations_for_id(&reflect.annotations, var_id)?;
 
-                // TODO: Can also define these as mut
-                let (set, binding) = annotations.iter().filter(|a| a.operands.len() >= 3).fold(
-                    (None, None),
-                    |state, a| {
-                        if let Operand::Decoration(d) = a.operands[1] {
-                            if let Operand::LiteralBit32(i) = a.operands[2] {
-                                if d == spirv::Decoration::DescriptorSet {
-                                    assert!(state.0.is_none(), "Set already has a value!");
-                                    return (Some(i), state.1);
-                                } else if d == spirv::Decoration::Binding {
-                                    assert!(state.1.is_none(), "Binding already has a value!");
-                                    return (state.0, Some(i));
-                                }
-                            }
+                let mut set = None;
+                let mut binding = None;
+                for annotation in annotations.iter().filter(|a| a.operands.len() >= 3) {
+                    if let (Operand::Decoration(d), Operand::LiteralBit32(i)) =
+                        (&annotation.operands[1], &annotation.operands[2])
+                    {
+                        let value = match d {
+                            spirv::Decoration::DescriptorSet => &mut set,
+                            spirv::Decoration::Binding => &mut binding,
+                            _ => continue,
+                        };
+                        if value.replace(*i).is_some() {
+                            return Err(ReflectError::DuplicateDecoration(var.clone(), *d));
                         }
-                        state
-                    },
-                );
+                    }
+                }
 
                 let set = set.ok_or_else(|| ReflectError::MissingSetDecoration(var.clone()))?;
                 let binding =
@@ -514,125 +1048,113 @@ impl Reflection {
                     descriptor_info.name = name.to_owned();
                 }
 
-                let inserted = current_set.insert(binding, descriptor_info);
-                assert!(
-                    inserted.is_none(),
-                    "Can't bind to the same slot twice within the same shader"
-                );
+                if let Some(&stages) = variable_stages.get(&var_id) {
+                    descriptor_info.is_statically_used = true;
+                    descriptor_info.stages = stages;
+                }
+                descriptor_info.access = self.get_declared_access(var_id, &descriptor_info)?;
+                descriptor_info.used_access =
+                    variable_accesses.get(&var_id).copied().unwrap_or_default();
+                descriptor_info.sampling =
+                    variable_sampling.get(&var_id).copied().unwrap_or_default();
+
+                if current_set.insert(binding, descriptor_info).is_some() {
+                    return Err(ReflectError::DuplicateBinding(set, binding));
+                }
             }
         }
         Ok(unique_sets)
     }
 
-    fn byte_offset_to_last_var(
-        reflect: &Module,
-        struct_instruction: &Instruction,
-    ) -> Result<u32, ReflectError> {
-        debug_assert!(struct_instruction.class.opcode == spirv::Op::TypeStruct);
-
-        // if there are less then two members there is no offset to use, early out
-        if struct_instruction.operands.len() < 2 {
-            return Ok(0);
-        }
+    pub fn get_push_constant_range(&self) -> Result<Option<PushConstantInfo>, ReflectError> {
+        self.push_constant_range(None)
+    }
 
-        let result_id = struct_instruction
-            .result_id
-            .ok_or_else(|| ReflectError::MissingResultId(struct_instruction.clone()))?;
-
-        // return the highest offset value
-        Ok(
-            Self::find_annotations_for_id(&reflect.annotations, result_id)?
-                .iter()
-                .filter(|i| i.class.opcode == spirv::Op::MemberDecorate)
-                .filter_map(|&i| match get_operand_at!(i, Operand::Decoration, 2) {
-                    Ok(spirv::Decoration::Offset) => {
-                        Some(get_operand_at!(i, Operand::LiteralBit32, 3))
-                    }
-                    Err(err) => Some(Err(err)),
-                    _ => None,
-                })
-                .collect::<Result<Vec<_>>>()?
-                .into_iter()
-                .max()
-                .unwrap_or(0),
-        )
+    /// Same as [`Self::get_push_constant_range()`], but only considers the push constant block
+    /// statically used by `entry_point`.
+    ///
+    /// This allows reflecting modules that declare a separate push constant block for each of
+    /// their entry points.
+    pub fn get_push_constant_range_for_entry_point(
+        &self,
+        entry_point: &EntryPoint,
+    ) -> Result<Option<PushConstantInfo>, ReflectError> {
+        self.push_constant_range(Some(entry_point))
     }
 
-    fn calculate_variable_size_bytes(
-        reflect: &Module,
-        type_instruction: &Instruction,
-    ) -> Result<u32, ReflectError> {
-        match type_instruction.class.opcode {
-            spirv::Op::TypeInt | spirv::Op::TypeFloat => {
-                debug_assert!(!type_instruction.operands.is_empty());
-                Ok(get_operand_at!(type_instruction, Operand::LiteralBit32, 0)? / 8)
-            }
-            spirv::Op::TypeVector | spirv::Op::TypeMatrix => {
-                debug_assert!(type_instruction.operands.len() == 2);
-                let type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
-                let var_type_instruction =
-                    Self::find_assignment_for(&reflect.types_global_values, type_id)?;
-                let type_size_bytes =
-                    Self::calculate_variable_size_bytes(reflect, var_type_instruction)?;
-
-                let type_constant_count =
-                    get_operand_at!(type_instruction, Operand::LiteralBit32, 1)?;
-                Ok(type_size_bytes * type_constant_count)
-            }
-            spirv::Op::TypeArray => {
-                debug_assert!(type_instruction.operands.len() == 2);
-                let type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
-                let var_type_instruction =
-                    Self::find_assignment_for(&reflect.types_global_values, type_id)?;
-                let type_size_bytes =
-                    Self::calculate_variable_size_bytes(reflect, var_type_instruction)?;
-
-                let var_constant_id = get_operand_at!(type_instruction, Operand::IdRef, 1)?;
-                let constant_instruction =
-                    Self::find_assignment_for(&reflect.types_global_values, var_constant_id)?;
-                let type_constant_count =
-                    get_operand_at!(constant_instruction, Operand::LiteralBit32, 0)?;
-
-                Ok(type_size_bytes * type_constant_count)
-            }
-            spirv::Op::TypeStruct => {
-                if !type_instruction.operands.is_empty() {
-                    let byte_offset = Self::byte_offset_to_last_var(reflect, type_instruction)?;
-                    let last_var_idx = type_instruction.operands.len() - 1;
-                    let id_ref = get_operand_at!(type_instruction, Operand::IdRef, last_var_idx)?;
-                    let type_instruction =
-                        Self::find_assignment_for(&reflect.types_global_values, id_ref)?;
-                    Ok(byte_offset
-                        + Self::calculate_variable_size_bytes(reflect, type_instruction)?)
-                } else {
-                    Ok(0)
-                }
-            }
-            spirv::Op::TypePointer => {
-                let memory_model = reflect
-                    .memory_model
-                    .as_ref()
-                    .ok_or(ReflectError::MissingMemoryModel)?;
-                let addressing_model = get_operand_at!(memory_model, Operand::AddressingModel, 0)?;
-
-                let storage_class = get_operand_at!(type_instruction, Operand::StorageClass, 0)?;
-
-                // https://registry.khronos.org/SPIR-V/specs/unified1/SPIRV.html#Addressing_Model
-                // https://registry.khronos.org/SPIR-V/specs/unified1/SPIRV.html#Storage_Class
-                match (addressing_model, storage_class) {
-                    (
-                        // https://github.com/KhronosGroup/SPIRV-Registry/blob/main/extensions/KHR/SPV_KHR_physical_storage_buffer.asciidoc
-                        spirv::AddressingModel::PhysicalStorageBuffer64,
-                        spirv::StorageClass::PhysicalStorageBuffer,
-                    ) => Ok(8),
-                    (a, s) => Err(ReflectError::InvalidAddressingModelAndStorageClass(a, s)),
+    fn push_constant_range(
+        &self,
+        entry_point: Option<&EntryPoint>,
+    ) -> Result<Option<PushConstantInfo>, ReflectError> {
+        let push_constant = match self.push_constant_variable(entry_point)? {
+            Some(push_constant) => push_constant,
+            None => return Ok(None),
+        };
+        let layout = self.get_push_constant_variable_layout(push_constant)?;
+
+        // Members may start at an offset to leave room for push constants of other stages
+        let offset = layout
+            .members
+            .iter()
+            .map(|member| member.offset)
+            .min()
+            .unwrap_or(0);
+
+        let stages = match entry_point {
+            Some(entry_point) => Self::entry_point_stage(entry_point),
+            None => {
+                let var_id = push_constant
+                    .result_id
+                    .ok_or_else(|| ReflectError::MissingResultId(push_constant.clone()))?;
+                let mut stages = ShaderStageFlags::NONE;
+                for entry_point in self.get_entry_points()? {
+                    if self
+                        .statically_used_variables(&entry_point)?
+                        .contains(&var_id)
+                    {
+                        stages |= Self::entry_point_stage(&entry_point);
+                    }
                 }
+                stages
             }
-            x => panic!("Size computation for {:?} unsupported", x),
-        }
+        };
+
+        Ok(Some(PushConstantInfo {
+            offset,
+            size: layout.size - offset,
+            stages,
+        }))
     }
 
-    pub fn get_push_constant_range(&self) -> Result<Option<PushConstantInfo>, ReflectError> {
+    /// Returns the member layout of the push constant block
+    pub fn get_push_constant_layout(&self) -> Result<Option<StructLayout>> {
+        self.push_constant_layout(None)
+    }
+
+    /// Same as [`Self::get_push_constant_layout()`], but only considers the push constant block
+    /// statically used by `entry_point`.
+    pub fn get_push_constant_layout_for_entry_point(
+        &self,
+        entry_point: &EntryPoint,
+    ) -> Result<Option<StructLayout>> {
+        self.push_constant_layout(Some(entry_point))
+    }
+
+    fn push_constant_layout(
+        &self,
+        entry_point: Option<&EntryPoint>,
+    ) -> Result<Option<StructLayout>> {
+        self.push_constant_variable(entry_point)?
+            .map(|push_constant| self.get_push_constant_variable_layout(push_constant))
+            .transpose()
+    }
+
+    /// Returns the `OpVariable` of the push constant block, or the one statically used by
+    /// `entry_point`
+    fn push_constant_variable(
+        &self,
+        entry_point: Option<&EntryPoint>,
+    ) -> Result<Option<&Instruction>> {
         let reflect = &self.0;
 
         let push_constants = reflect
@@ -649,36 +1171,60 @@ impl Reflection {
             })
             .collect::<Result<Vec<_>>>()?;
 
+        // An entry point can statically use at most one push constant block, even if the module
+        // declares one for each of its entry points
+        let push_constants = match entry_point {
+            Some(entry_point) => {
+                let used_variables = self.statically_used_variables(entry_point)?;
+                push_constants
+                    .into_iter()
+                    .filter(|i| matches!(i.result_id, Some(id) if used_variables.contains(&id)))
+                    .collect()
+            }
+            None => push_constants,
+        };
+
         if push_constants.len() > 1 {
             return Err(ReflectError::TooManyPushConstants);
         }
 
-        let push_constant = match push_constants.into_iter().next() {
-            Some(push_constant) => push_constant,
-            None => return Ok(None),
-        };
+        Ok(push_constants.into_iter().next())
+    }
+
+    fn get_push_constant_variable_layout(
+        &self,
+        push_constant: &Instruction,
+    ) -> Result<StructLayout> {
+        let reflect = &self.0;
 
         let instruction = Reflection::find_assignment_for(
             &reflect.types_global_values,
-            push_constant.result_type.unwrap(),
+            push_constant
+                .result_type
+                .ok_or_else(|| ReflectError::VariableWithoutReturnType(push_constant.clone()))?,
         )?;
 
         // resolve type if the type instruction is a pointer
         let instruction = if instruction.class.opcode == spirv::Op::TypePointer {
             let ptr_storage_class = get_operand_at!(instruction, Operand::StorageClass, 0)?;
-            assert_eq!(spirv::StorageClass::PushConstant, ptr_storage_class);
+            if ptr_storage_class != spirv::StorageClass::PushConstant {
+                return Err(ReflectError::StorageClassMismatch(
+                    instruction.clone(),
+                    spirv::StorageClass::PushConstant,
+                ));
+            }
             let element_type_id = get_operand_at!(instruction, Operand::IdRef, 1)?;
-            Reflection::find_assignment_for(&reflect.types_global_values, element_type_id)?
+            self.find_referenced_type(instruction, element_type_id)?
         } else {
             instruction
         };
 
-        let size_bytes = Self::calculate_variable_size_bytes(reflect, instruction)?;
+        self.get_struct_layout(instruction)
+    }
 
-        Ok(Some(PushConstantInfo {
-            size: size_bytes,
-            offset: 0,
-        }))
+    /// Returns the stage of `entry_point`, OpenCL kernels have no Vulkan shader stage
+    fn entry_point_stage(entry_point: &EntryPoint) -> ShaderStageFlags {
+        entry_point.stage().unwrap_or_default()
     }
 
     pub fn disassemble(&self) -> String {
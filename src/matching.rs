@@ -0,0 +1,190 @@
+//! Matching the outputs of a shader stage against the inputs of the next stage
+
+use crate::{EntryPoint, InterfaceVariable, ReflectError, Reflection, Result};
+use rspirv::spirv;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+/// A difference between the outputs of a shader stage and the inputs of the next stage, see
+/// [`Reflection::match_interface()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InterfaceMismatch {
+    /// An input whose locations no output is written to, which reads undefined values
+    MissingOutput { input: InterfaceVariable },
+    /// An output that is not read by the next stage.  This is informational, unused outputs are
+    /// valid and commonly shared between pipelines.
+    UnusedOutput { output: InterfaceVariable },
+    /// An output and input occupying overlapping locations and components that do not start at
+    /// the same location and component, or differ in type, array dimensions (not counting the
+    /// per-vertex array) or `patch` or `per_primitive` qualifier
+    TypeMismatch {
+        output: InterfaceVariable,
+        input: InterfaceVariable,
+    },
+    /// An output with interpolation decorations that the matching input lacks.  This is
+    /// informational: interpolation is determined by the decorations of the fragment shader
+    /// input, the decorations of outputs have no effect.
+    ///
+    /// Decorations only present on the input, such as `flat` on an integer fragment shader
+    /// input, are not reported.
+    InterpolationMismatch {
+        output: InterfaceVariable,
+        input: InterfaceVariable,
+    },
+}
+
+impl InterfaceMismatch {
+    /// Returns whether this mismatch makes the next stage read undefined or misinterpreted
+    /// values, as opposed to [`Self::UnusedOutput`] and [`Self::InterpolationMismatch`] which
+    /// are informational
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::MissingOutput { .. } | Self::TypeMismatch { .. })
+    }
+}
+
+/// Returns the locations and the components within them occupied by `variable` starting at
+/// `(location, component)`.  Arrays and matrices occupy consecutive locations, and 64-bit
+/// vectors two components per element, spanning a second location if they do not fit in one.
+fn occupied_range(
+    (location, component): (u32, u32),
+    variable: &InterfaceVariable,
+) -> (Range<u32>, Range<u32>) {
+    let ty = &variable.ty;
+    let location_count = variable
+        .array_dims
+        .iter()
+        .fold(ty.location_count(), |count, &dim| count.saturating_mul(dim));
+    let components = if ty.location_count() > ty.column_count {
+        0..4
+    } else {
+        let width = ty.scalar.width().unwrap_or(32).max(32) / 32;
+        component..component.saturating_add(ty.vector_size.saturating_mul(width))
+    };
+    (
+        location..location.saturating_add(location_count),
+        components,
+    )
+}
+
+fn overlaps(a: &(Range<u32>, Range<u32>), b: &(Range<u32>, Range<u32>)) -> bool {
+    let overlap = |a: &Range<u32>, b: &Range<u32>| a.start < b.end && b.start < a.end;
+    overlap(&a.0, &b.0) && overlap(&a.1, &b.1)
+}
+
+/// Returns whether the non-patch `Input` or `Output` variables of `execution_model` have an
+/// outermost per-vertex (or per-primitive) array dimension
+fn is_arrayed(execution_model: spirv::ExecutionModel, storage_class: spirv::StorageClass) -> bool {
+    use spirv::ExecutionModel as E;
+    use spirv::StorageClass as S;
+    matches!(
+        (execution_model, storage_class),
+        (E::TessellationControl, _)
+            | (E::TessellationEvaluation, S::Input)
+            | (E::Geometry, S::Input)
+            | (E::MeshEXT, S::Output)
+            | (E::MeshNV, S::Output)
+    )
+}
+
+impl Reflection {
+    /// Returns the non-builtin variables of `entry_point` in `storage_class`, keyed by location
+    /// and component, with the per-vertex array dimension removed
+    fn get_located_variables(
+        &self,
+        entry_point: &EntryPoint,
+        storage_class: spirv::StorageClass,
+    ) -> Result<BTreeMap<(u32, u32), InterfaceVariable>> {
+        let arrayed = is_arrayed(entry_point.execution_model, storage_class);
+        let mut variables = BTreeMap::new();
+        for mut variable in self.get_interface_variables(entry_point)? {
+            if variable.storage_class != storage_class || variable.is_builtin() {
+                continue;
+            }
+            let location = variable
+                .location
+                .ok_or_else(|| ReflectError::MissingLocationDecoration(variable.name.clone()))?;
+            if arrayed && !variable.patch && !variable.array_dims.is_empty() {
+                variable.array_dims.remove(0);
+            }
+            variables.insert((location, variable.component), variable);
+        }
+        Ok(variables)
+    }
+
+    /// Matches the `Output` variables of `entry_point` against the `Input` variables of
+    /// `next_entry_point` in `next`, ie. a vertex and a fragment shader.  Longer chains of
+    /// tessellation, geometry or mesh shaders are checked one pair of consecutive stages at a
+    /// time.
+    ///
+    /// Every input is matched with the output that starts at the same `Location` and
+    /// `Component`.  Without one, it is compared against the first output whose occupied
+    /// locations and components overlap its own, ie. a `vec4[2]` output at location 0 and a
+    /// `vec4` input at location 1, which is reported as a [`InterfaceMismatch::TypeMismatch`].
+    /// Builtins are not checked.  The per-vertex array of tessellation, geometry and mesh shader
+    /// variables is not part of the comparison, and is not included in the returned variables
+    /// either.
+    ///
+    /// Returns the mismatches of all inputs followed by all outputs that do not overlap any
+    /// input, each sorted by location and component, or an empty `Vec` if the interfaces match.
+    /// Not all mismatches are errors, see [`InterfaceMismatch::is_error()`].  Fails with
+    /// [`ReflectError::MissingLocationDecoration`] if a variable lacks a location.
+    pub fn match_interface(
+        &self,
+        entry_point: &EntryPoint,
+        next: &Reflection,
+        next_entry_point: &EntryPoint,
+    ) -> Result<Vec<InterfaceMismatch>> {
+        let outputs = self.get_located_variables(entry_point, spirv::StorageClass::Output)?;
+        let inputs = next.get_located_variables(next_entry_point, spirv::StorageClass::Input)?;
+
+        let mut used = BTreeSet::new();
+        let mut mismatches = vec![];
+        for (key, input) in inputs {
+            let range = occupied_range(key, &input);
+            let overlapping = outputs
+                .iter()
+                .filter(|&(&output_key, output)| {
+                    overlaps(&range, &occupied_range(output_key, output))
+                })
+                .map(|(&output_key, _)| output_key)
+                .collect::<Vec<_>>();
+            used.extend(overlapping.iter().copied());
+
+            let output = match outputs.get_key_value(&key).or_else(|| {
+                overlapping
+                    .first()
+                    .and_then(|output_key| outputs.get_key_value(output_key))
+            }) {
+                Some((&output_key, output)) => {
+                    used.insert(output_key);
+                    output.clone()
+                }
+                None => {
+                    mismatches.push(InterfaceMismatch::MissingOutput { input });
+                    continue;
+                }
+            };
+
+            if output.location != input.location
+                || output.component != input.component
+                || output.ty != input.ty
+                || output.array_dims != input.array_dims
+                || output.patch != input.patch
+                || output.per_primitive != input.per_primitive
+            {
+                mismatches.push(InterfaceMismatch::TypeMismatch { output, input });
+            } else if !input.interpolation.contains(output.interpolation) {
+                mismatches.push(InterfaceMismatch::InterpolationMismatch { output, input });
+            }
+        }
+        mismatches.extend(
+            outputs
+                .into_iter()
+                .filter(|(key, _)| !used.contains(key))
+                .map(|(_, output)| InterfaceMismatch::UnusedOutput { output }),
+        );
+
+        Ok(mismatches)
+    }
+}
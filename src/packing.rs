@@ -0,0 +1,320 @@
+//! Validating block layouts against the packing rules of shading languages
+
+use crate::{NumericType, StructLayout, TypeLayout};
+use rspirv::spirv;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Rules that shader compilers use to assign `Offset`, `ArrayStride` and `MatrixStride`
+/// decorations to the members of blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutRule {
+    /// GLSL `std140`, the default for uniform buffers: arrays, matrix columns and structs are
+    /// aligned to 16 bytes
+    Std140,
+    /// GLSL `std430`, the default for storage buffers and push constants
+    Std430,
+    /// `VK_EXT_scalar_block_layout`, as produced by GLSL `scalar` and DXC's
+    /// `-fvk-use-scalar-layout`: everything is aligned to its scalar type
+    Scalar,
+    /// Packing of HLSL constant buffers, as produced by DXC's `-fvk-use-dx-layout`: members are
+    /// packed into 16-byte registers, vectors cannot straddle two registers, and arrays, matrix
+    /// columns and structs start at a new register without padding their last register
+    HlslCbuffer,
+}
+
+impl LayoutRule {
+    pub const ALL: [Self; 4] = [Self::Std140, Self::Std430, Self::Scalar, Self::HlslCbuffer];
+}
+
+/// A member whose decoration does not match the value assigned by a [`LayoutRule`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutViolation {
+    /// Path to the member from the validated struct, where `.` separates the members of nested
+    /// structs and `[]` denotes the elements of an array.  Unnamed members are referred to by
+    /// their index.
+    pub member: String,
+    /// Either `Offset`, `ArrayStride` or `MatrixStride`
+    pub decoration: spirv::Decoration,
+    pub actual: u32,
+    pub expected: u32,
+}
+
+fn round_up(value: u32, alignment: u32) -> u32 {
+    match value.checked_rem(alignment).unwrap_or(0) {
+        0 => value,
+        remainder => value.saturating_add(alignment - remainder),
+    }
+}
+
+/// Returns the size of the scalars of `ty` in bytes.  Booleans have no defined size and never
+/// appear in a [`StructLayout`].
+fn scalar_size(ty: &NumericType) -> u32 {
+    ty.scalar.width().unwrap_or(32) / 8
+}
+
+/// Returns the type of a single column (or row) of a matrix, and the number of them
+fn matrix_vectors(ty: &NumericType, row_major: bool) -> (NumericType, u32) {
+    let (major_count, minor_count) = if row_major {
+        (ty.vector_size, ty.column_count)
+    } else {
+        (ty.column_count, ty.vector_size)
+    };
+    let minor = NumericType {
+        vector_size: minor_count,
+        column_count: 1,
+        ..*ty
+    };
+    (minor, major_count)
+}
+
+impl LayoutRule {
+    fn vector_alignment(self, ty: &NumericType) -> u32 {
+        let scalar = scalar_size(ty);
+        match self {
+            Self::Std140 | Self::Std430 => match ty.vector_size {
+                1 => scalar,
+                2 => 2 * scalar,
+                _ => 4 * scalar,
+            },
+            Self::Scalar | Self::HlslCbuffer => scalar,
+        }
+    }
+
+    /// Returns the stride of the columns (or rows) of a matrix
+    fn matrix_stride(self, ty: &NumericType, row_major: bool) -> u32 {
+        let (minor, _) = matrix_vectors(ty, row_major);
+        match self {
+            Self::Std140 => round_up(self.vector_alignment(&minor), 16),
+            Self::Std430 => self.vector_alignment(&minor),
+            Self::Scalar => minor.vector_size.saturating_mul(scalar_size(&minor)),
+            Self::HlslCbuffer => 16,
+        }
+    }
+
+    /// Returns the size in bytes of `ty` based on its actual strides, which for HLSL excludes
+    /// the padding in the last register of arrays and matrices
+    fn size(self, ty: &TypeLayout) -> u32 {
+        let sequence = |stride: u32, count: u32, last: u32| match self {
+            Self::HlslCbuffer if count > 0 => stride.saturating_mul(count - 1).saturating_add(last),
+            _ => stride.saturating_mul(count),
+        };
+        match ty {
+            TypeLayout::Numeric(ty) => ty.vector_size.saturating_mul(scalar_size(ty)),
+            TypeLayout::Matrix {
+                ty,
+                stride,
+                row_major,
+            } => {
+                let (minor, major_count) = matrix_vectors(ty, *row_major);
+                sequence(*stride, major_count, self.size(&TypeLayout::Numeric(minor)))
+            }
+            TypeLayout::Array {
+                element,
+                length,
+                stride,
+            } => sequence(
+                *stride,
+                length
+                    .and_then(|length| length.default_length())
+                    .unwrap_or(0),
+                self.size(element),
+            ),
+            TypeLayout::Struct(layout) => layout.size,
+            TypeLayout::PhysicalPointer => 8,
+        }
+    }
+}
+
+/// Validates structs against a single [`LayoutRule`].  Structs are shared between all members
+/// of their type, so the alignment and violations of every distinct struct are only determined
+/// once rather than once per occurrence.
+struct Validator {
+    rule: LayoutRule,
+    /// Stops at the first violation when only the outcome is of interest
+    first_only: bool,
+    violations: Vec<LayoutViolation>,
+    /// Address of every struct whose members were validated already
+    validated: HashSet<*const StructLayout>,
+    alignments: HashMap<*const StructLayout, u32>,
+}
+
+impl Validator {
+    fn new(rule: LayoutRule, first_only: bool) -> Self {
+        Self {
+            rule,
+            first_only,
+            violations: vec![],
+            validated: HashSet::new(),
+            alignments: HashMap::new(),
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.first_only && !self.violations.is_empty()
+    }
+
+    /// Returns the alignment in bytes of a member of type `ty`
+    fn alignment(&mut self, ty: &TypeLayout) -> u32 {
+        let rule = self.rule;
+        let aggregate = |alignment: u32| match rule {
+            LayoutRule::Std140 | LayoutRule::HlslCbuffer => round_up(alignment, 16),
+            LayoutRule::Std430 | LayoutRule::Scalar => alignment,
+        };
+        match ty {
+            TypeLayout::Numeric(ty) => rule.vector_alignment(ty),
+            TypeLayout::Matrix { ty, row_major, .. } => {
+                aggregate(rule.vector_alignment(&matrix_vectors(ty, *row_major).0))
+            }
+            TypeLayout::Array { element, .. } => aggregate(self.alignment(element)),
+            TypeLayout::Struct(layout) => {
+                let key = Arc::as_ptr(layout);
+                if let Some(&alignment) = self.alignments.get(&key) {
+                    return alignment;
+                }
+                let alignment = aggregate(
+                    layout
+                        .members
+                        .iter()
+                        .map(|member| self.alignment(&member.ty))
+                        .max()
+                        .unwrap_or(1),
+                );
+                self.alignments.insert(key, alignment);
+                alignment
+            }
+            TypeLayout::PhysicalPointer => 8,
+        }
+    }
+
+    /// Returns the offset of a member of type `ty` following a member that ends at `end`
+    fn offset(&mut self, ty: &TypeLayout, end: u32) -> u32 {
+        let offset = round_up(end, self.alignment(ty));
+        match (self.rule, ty) {
+            // Vectors are moved to the next register rather than straddling two of them, and
+            // 64-bit vectors larger than a register always start at one
+            (LayoutRule::HlslCbuffer, TypeLayout::Numeric(_))
+                if offset % 16 + self.rule.size(ty).min(16) > 16 =>
+            {
+                round_up(offset, 16)
+            }
+            _ => offset,
+        }
+    }
+
+    /// Returns the end of a member of type `ty` placed at `offset`, including any padding that
+    /// following members cannot be placed in
+    fn end(&mut self, ty: &TypeLayout, offset: u32) -> u32 {
+        let end = offset.saturating_add(self.rule.size(ty));
+        match (self.rule, ty) {
+            (LayoutRule::HlslCbuffer, _) | (_, TypeLayout::Numeric(_)) => end,
+            _ => round_up(end, self.alignment(ty)),
+        }
+    }
+
+    fn violation(&mut self, path: &str, decoration: spirv::Decoration, actual: u32, expected: u32) {
+        self.violations.push(LayoutViolation {
+            member: path.to_owned(),
+            decoration,
+            actual,
+            expected,
+        });
+    }
+
+    fn validate_type(&mut self, path: &str, ty: &TypeLayout) {
+        match ty {
+            TypeLayout::Matrix {
+                ty,
+                stride,
+                row_major,
+            } => {
+                let expected = self.rule.matrix_stride(ty, *row_major);
+                if *stride != expected {
+                    self.violation(path, spirv::Decoration::MatrixStride, *stride, expected);
+                }
+            }
+            TypeLayout::Array {
+                element, stride, ..
+            } => {
+                let expected = round_up(self.rule.size(element), self.alignment(ty));
+                if *stride != expected {
+                    self.violation(path, spirv::Decoration::ArrayStride, *stride, expected);
+                }
+                self.validate_type(&format!("{}[]", path), element);
+            }
+            TypeLayout::Struct(layout) => {
+                if self.validated.insert(Arc::as_ptr(layout)) {
+                    self.validate_struct(path, layout)
+                }
+            }
+            TypeLayout::Numeric(_) | TypeLayout::PhysicalPointer => {}
+        }
+    }
+
+    fn validate_struct(&mut self, path: &str, layout: &StructLayout) {
+        let mut members = layout.members.iter().enumerate().collect::<Vec<_>>();
+        members.sort_by_key(|(_, member)| member.offset);
+
+        let mut end = None;
+        for (idx, member) in members {
+            if self.done() {
+                return;
+            }
+            let name = if member.name.is_empty() {
+                idx.to_string()
+            } else {
+                member.name.clone()
+            };
+            let path = if path.is_empty() {
+                name
+            } else {
+                format!("{}.{}", path, name)
+            };
+
+            // Offsets are validated relative to the actual end of the previous member, so that
+            // a single misplaced member does not invalidate all members that follow it
+            let expected = match end {
+                Some(end) => self.offset(&member.ty, end),
+                None => 0,
+            };
+            if member.offset != expected {
+                self.violation(&path, spirv::Decoration::Offset, member.offset, expected);
+            }
+            self.validate_type(&path, &member.ty);
+            end = Some(self.end(&member.ty, member.offset));
+        }
+    }
+}
+
+impl StructLayout {
+    /// Returns every member whose `Offset`, `ArrayStride` or `MatrixStride` decoration differs
+    /// from the value `rule` assigns to it, including members of nested structs.
+    ///
+    /// Offsets are compared against the position `rule` would assign right after the previous
+    /// member, so explicit offsets that leave a gap (such as GLSL `layout(offset = ...)` and
+    /// HLSL `packoffset`) are reported as well.  Violations within a struct type that is nested
+    /// more than once are only reported for its first occurrence.
+    pub fn validate(&self, rule: LayoutRule) -> Vec<LayoutViolation> {
+        let mut validator = Validator::new(rule, false);
+        validator.validate_struct("", self);
+        validator.violations
+    }
+
+    /// Returns all rules that this layout follows, see [`Self::validate()`].
+    ///
+    /// Simple blocks can follow multiple rules, for example a block containing only `vec4`s is
+    /// laid out the same under all of them.
+    pub fn layout_rules(&self) -> Vec<LayoutRule> {
+        LayoutRule::ALL
+            .iter()
+            .copied()
+            .filter(|&rule| {
+                let mut validator = Validator::new(rule, true);
+                validator.validate_struct("", self);
+                validator.violations.is_empty()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,119 @@
+//! Merging the reflection of multiple shader stages into a single pipeline layout
+
+use crate::{DescriptorInfo, EntryPoint, PushConstantInfo, ReflectError, Reflection, Result};
+use std::collections::BTreeMap;
+
+/// Descriptor sets and push constant ranges of all entry points that make up a pipeline
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PipelineReflection {
+    /// Descriptor information of the first stage that declared each binding, with
+    /// [`DescriptorInfo::stages`], [`DescriptorInfo::is_statically_used`],
+    /// [`DescriptorInfo::access`], [`DescriptorInfo::used_access`] and
+    /// [`DescriptorInfo::sampling`] combined over all stages
+    pub descriptor_sets: BTreeMap<u32, BTreeMap<u32, DescriptorInfo>>,
+    /// One range per distinct offset and size, shared by all stages that use it
+    pub push_constant_ranges: Vec<PushConstantInfo>,
+}
+
+impl PipelineReflection {
+    /// Merges all entry points of all `modules`, ie. a vertex and a fragment shader or every
+    /// shader of a ray tracing pipeline.
+    ///
+    /// Conflicting bindings are reported like in [`Self::add_entry_point()`], collected over all
+    /// entry points.
+    pub fn new(modules: &[Reflection]) -> Result<Self> {
+        let mut pipeline = Self::default();
+        let mut conflicts = vec![];
+        for module in modules {
+            for entry_point in module.get_entry_points()? {
+                pipeline.merge_entry_point(module, &entry_point, &mut conflicts)?;
+            }
+        }
+        check_conflicts(conflicts)?;
+        Ok(pipeline)
+    }
+
+    /// Merges the descriptors and push constants of a single `entry_point` of `module` into the
+    /// pipeline.
+    ///
+    /// Fails with [`ReflectError::ConflictingDescriptorType`] or
+    /// [`ReflectError::ConflictingBindingCount`] if a binding was already declared differently
+    /// by another stage, or with [`ReflectError::ConflictingBindings`] listing all of them if
+    /// there are several.  Bindings that do not conflict are still merged.
+    pub fn add_entry_point(&mut self, module: &Reflection, entry_point: &EntryPoint) -> Result<()> {
+        let mut conflicts = vec![];
+        self.merge_entry_point(module, entry_point, &mut conflicts)?;
+        check_conflicts(conflicts)
+    }
+
+    /// Merges `entry_point` into the pipeline, appending bindings that conflict with a previous
+    /// stage to `conflicts` instead of failing on the first one
+    fn merge_entry_point(
+        &mut self,
+        module: &Reflection,
+        entry_point: &EntryPoint,
+        conflicts: &mut Vec<ReflectError>,
+    ) -> Result<()> {
+        for (set, bindings) in module.get_descriptor_sets_for_entry_point(entry_point)? {
+            let pipeline_set = self.descriptor_sets.entry(set).or_default();
+            for (binding, info) in bindings {
+                let existing = match pipeline_set.get_mut(&binding) {
+                    Some(existing) => existing,
+                    None => {
+                        pipeline_set.insert(binding, info);
+                        continue;
+                    }
+                };
+
+                if existing.ty != info.ty {
+                    conflicts.push(ReflectError::ConflictingDescriptorType(
+                        set,
+                        binding,
+                        existing.ty,
+                        info.ty,
+                    ));
+                    continue;
+                }
+                if existing.binding_count != info.binding_count {
+                    conflicts.push(ReflectError::ConflictingBindingCount(
+                        set,
+                        binding,
+                        existing.binding_count.clone(),
+                        info.binding_count,
+                    ));
+                    continue;
+                }
+
+                existing.stages |= info.stages;
+                existing.is_statically_used |= info.is_statically_used;
+                existing.access |= info.access;
+                existing.used_access |= info.used_access;
+                existing.sampling |= info.sampling;
+            }
+        }
+
+        if let Some(range) = module.get_push_constant_range_for_entry_point(entry_point)? {
+            match self
+                .push_constant_ranges
+                .iter_mut()
+                .find(|r| r.offset == range.offset && r.size == range.size)
+            {
+                Some(existing) => existing.stages |= range.stages,
+                None => self.push_constant_ranges.push(range),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the only conflict as is, or all of them wrapped in
+/// [`ReflectError::ConflictingBindings`]
+fn check_conflicts(mut conflicts: Vec<ReflectError>) -> Result<()> {
+    match conflicts.len() {
+        0 => Ok(()),
+        1 => Err(conflicts.remove(0)),
+        _ => Err(ReflectError::ConflictingBindings(conflicts)),
+    }
+}
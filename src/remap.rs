@@ -0,0 +1,79 @@
+//! Rewriting descriptor bindings in the reflected module
+
+use crate::{DescriptorInfo, ReflectError, Reflection, Result};
+use rspirv::dr::Operand;
+use rspirv::spirv;
+use std::collections::{BTreeMap, BTreeSet};
+
+impl Reflection {
+    /// Moves every descriptor to the `(set, binding)` returned by `remap`, which is called with
+    /// the current set, binding and descriptor information.
+    ///
+    /// This rewrites the `DescriptorSet` and `Binding` decorations of the module held by this
+    /// [`Reflection`]; use [`Self::assemble()`] to retrieve the patched SPIR-V binary.  Fails with
+    /// [`ReflectError::RemappedBindingCollision`] if two descriptors would end up in the same slot;
+    /// the module is left unmodified whenever an error is returned.
+    pub fn remap_bindings(
+        &mut self,
+        mut remap: impl FnMut(u32, u32, &DescriptorInfo) -> (u32, u32),
+    ) -> Result<()> {
+        let sets = self.get_descriptor_sets()?;
+
+        let mut remapped = BTreeMap::new();
+        for var in &self.0.types_global_values {
+            if var.class.opcode != spirv::Op::Variable {
+                continue;
+            }
+            let var_id = var
+                .result_id
+                .ok_or_else(|| ReflectError::MissingResultId(var.clone()))?;
+            let set = self.get_decoration_literal(var_id, spirv::Decoration::DescriptorSet)?;
+            let binding = self.get_decoration_literal(var_id, spirv::Decoration::Binding)?;
+            if let (Some(set), Some(binding)) = (set, binding) {
+                if let Some(info) = sets.get(&set).and_then(|bindings| bindings.get(&binding)) {
+                    remapped.insert(var_id, remap(set, binding, info));
+                }
+            }
+        }
+
+        let mut slots = BTreeSet::new();
+        for &(set, binding) in remapped.values() {
+            if !slots.insert((set, binding)) {
+                return Err(ReflectError::RemappedBindingCollision(set, binding));
+            }
+        }
+
+        // Validate every decoration before overwriting any of them, so that a malformed one
+        // leaves the module untouched
+        let mut writes = vec![];
+        for (idx, annotation) in self.0.annotations.iter().enumerate() {
+            if annotation.class.opcode != spirv::Op::Decorate {
+                continue;
+            }
+            let (set, binding) =
+                match remapped.get(&get_operand_at!(annotation, Operand::IdRef, 0)?) {
+                    Some(&remapped) => remapped,
+                    None => continue,
+                };
+            let value = match get_operand_at!(annotation, Operand::Decoration, 1)? {
+                spirv::Decoration::DescriptorSet => set,
+                spirv::Decoration::Binding => binding,
+                _ => continue,
+            };
+            get_operand_at!(annotation, Operand::LiteralBit32, 2)?;
+            writes.push((idx, value));
+        }
+
+        for (idx, value) in writes {
+            self.0.annotations[idx].operands[2] = Operand::LiteralBit32(value);
+        }
+
+        Ok(())
+    }
+
+    /// Assembles the module held by this [`Reflection`] back into a SPIR-V binary
+    pub fn assemble(&self) -> Vec<u32> {
+        use rspirv::binary::Assemble;
+        self.0.assemble()
+    }
+}
@@ -0,0 +1,23 @@
+//! Sampling of images and samplers with and without depth comparison
+
+/// Bitmask of the ways in which a shader samples an image or sampler
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[repr(transparent)]
+pub struct SamplingFlags(pub u32);
+
+impl SamplingFlags {
+    pub const NONE: Self = Self(0);
+    /// Sampled or gathered without a depth reference, which requires a non-comparison sampler
+    /// and, for filtering samplers, a filterable image
+    pub const SAMPLE: Self = Self(1);
+    /// Sampled or gathered with a depth reference (`OpImage*Dref*`), which requires a comparison
+    /// sampler and a depth image
+    pub const COMPARE: Self = Self(2);
+}
+
+impl_flags!(SamplingFlags, [SAMPLE, COMPARE]);
@@ -0,0 +1,301 @@
+//! Reflection of specialization constants
+
+use crate::{ReflectError, Reflection, Result, ScalarType};
+use rspirv::dr::{Instruction, Operand};
+use rspirv::spirv;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpecializationConstantValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    /// Value of a 16, 32 or 64-bit float, widened to `f64` without loss of precision
+    Float(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpecializationConstant {
+    /// `constant_id` to use in `VkSpecializationMapEntry`
+    pub spec_id: u32,
+    pub name: String,
+    pub ty: ScalarType,
+    pub default_value: SpecializationConstantValue,
+}
+
+impl SpecializationConstant {
+    /// Size in bytes of the value in `VkSpecializationInfo::pData`.
+    ///
+    /// Booleans are passed as 32-bit `VkBool32`.
+    pub fn size(&self) -> u32 {
+        self.ty.width().unwrap_or(32) / 8
+    }
+}
+
+/// Converts the bits of an IEEE 754 half-precision float to `f64`
+fn f16_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f64::from(bits & 0x3ff);
+    sign * match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        0x1f if mantissa == 0.0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+/// Integer or boolean constant evaluated with the default values of all specialization
+/// constants
+#[derive(Clone, Copy)]
+struct DefaultValue {
+    /// Bits of the value, with all bits above `width` cleared
+    bits: u64,
+    /// Width in bits, `1` for booleans
+    width: u32,
+}
+
+impl DefaultValue {
+    fn new(bits: u64, width: u32) -> Self {
+        let mask = u64::MAX >> (64 - width);
+        Self {
+            bits: bits & mask,
+            width,
+        }
+    }
+
+    fn signed(self) -> i64 {
+        let shift = 64 - self.width;
+        ((self.bits << shift) as i64) >> shift
+    }
+
+    /// Shift amount given by `self`, `None` if it exceeds the width of the shifted value
+    fn shift(self, width: u32) -> Option<u32> {
+        Some(self.bits)
+            .filter(|&bits| bits < u64::from(width))
+            .map(|bits| bits as u32)
+    }
+}
+
+/// Evaluates the integer and logical operations that `OpSpecConstantOp` allows in shaders,
+/// `None` if the operation is not supported or its result is undefined
+fn evaluate_spec_constant_op(op: spirv::Op, args: &[DefaultValue]) -> Option<u64> {
+    use spirv::Op::*;
+    Some(match (op, args) {
+        (SConvert, [a]) => a.signed() as u64,
+        (UConvert, [a]) => a.bits,
+        (SNegate, [a]) => a.bits.wrapping_neg(),
+        (Not, [a]) => !a.bits,
+        (LogicalNot, [a]) => u64::from(a.bits == 0),
+        (IAdd, [a, b]) => a.bits.wrapping_add(b.bits),
+        (ISub, [a, b]) => a.bits.wrapping_sub(b.bits),
+        (IMul, [a, b]) => a.bits.wrapping_mul(b.bits),
+        (UDiv, [a, b]) => a.bits.checked_div(b.bits)?,
+        (UMod, [a, b]) => a.bits.checked_rem(b.bits)?,
+        (SDiv, [a, b]) => a.signed().checked_div(b.signed())? as u64,
+        (SRem, [a, b]) => a.signed().checked_rem(b.signed())? as u64,
+        // The sign of the result follows the divisor rather than the dividend
+        (SMod, [a, b]) => match a.signed().checked_rem(b.signed())? {
+            r if r != 0 && (r < 0) != (b.signed() < 0) => (r + b.signed()) as u64,
+            r => r as u64,
+        },
+        (ShiftRightLogical, [a, b]) => a.bits >> b.shift(a.width)?,
+        (ShiftRightArithmetic, [a, b]) => (a.signed() >> b.shift(a.width)?) as u64,
+        (ShiftLeftLogical, [a, b]) => a.bits << b.shift(a.width)?,
+        (BitwiseOr | LogicalOr, [a, b]) => a.bits | b.bits,
+        (BitwiseXor, [a, b]) => a.bits ^ b.bits,
+        (BitwiseAnd | LogicalAnd, [a, b]) => a.bits & b.bits,
+        (IEqual | LogicalEqual, [a, b]) => u64::from(a.bits == b.bits),
+        (INotEqual | LogicalNotEqual, [a, b]) => u64::from(a.bits != b.bits),
+        (ULessThan, [a, b]) => u64::from(a.bits < b.bits),
+        (SLessThan, [a, b]) => u64::from(a.signed() < b.signed()),
+        (UGreaterThan, [a, b]) => u64::from(a.bits > b.bits),
+        (SGreaterThan, [a, b]) => u64::from(a.signed() > b.signed()),
+        (ULessThanEqual, [a, b]) => u64::from(a.bits <= b.bits),
+        (SLessThanEqual, [a, b]) => u64::from(a.signed() <= b.signed()),
+        (UGreaterThanEqual, [a, b]) => u64::from(a.bits >= b.bits),
+        (SGreaterThanEqual, [a, b]) => u64::from(a.signed() >= b.signed()),
+        (Select, [condition, a, b]) => {
+            if condition.bits != 0 {
+                a.bits
+            } else {
+                b.bits
+            }
+        }
+        _ => return None,
+    })
+}
+
+impl Reflection {
+    /// Returns all specialization constants that are decorated with a `SpecId`, sorted by their
+    /// id.
+    ///
+    /// `OpSpecConstantComposite` and `OpSpecConstantOp` results cannot be specialized directly and
+    /// are not returned.
+    pub fn get_specialization_constants(&self) -> Result<Vec<SpecializationConstant>> {
+        let names = self.get_names()?;
+        let mut constants = vec![];
+
+        for constant in &self.0.types_global_values {
+            if !matches!(
+                constant.class.opcode,
+                spirv::Op::SpecConstant
+                    | spirv::Op::SpecConstantTrue
+                    | spirv::Op::SpecConstantFalse
+            ) {
+                continue;
+            }
+
+            let id = constant
+                .result_id
+                .ok_or_else(|| ReflectError::MissingResultId(constant.clone()))?;
+            let spec_id = match self.get_decoration_literal(id, spirv::Decoration::SpecId)? {
+                Some(spec_id) => spec_id,
+                None => continue,
+            };
+
+            let type_id = constant
+                .result_type
+                .ok_or_else(|| ReflectError::MissingResultType(constant.clone()))?;
+            let type_instruction = Self::find_assignment_for(&self.0.types_global_values, type_id)?;
+            let ty = self.get_numeric_type(type_instruction)?;
+            if ty.vector_size != 1 || ty.column_count != 1 {
+                return Err(ReflectError::UnhandledTypeInstruction(
+                    type_instruction.clone(),
+                ));
+            }
+
+            constants.push(SpecializationConstant {
+                spec_id,
+                name: names.get(&id).cloned().unwrap_or_default(),
+                ty: ty.scalar,
+                default_value: Self::get_spec_constant_value(constant, ty.scalar)?,
+            });
+        }
+
+        constants.sort_by_key(|constant| constant.spec_id);
+
+        Ok(constants)
+    }
+
+    fn get_spec_constant_value(
+        constant: &Instruction,
+        ty: ScalarType,
+    ) -> Result<SpecializationConstantValue> {
+        let bits = match constant.class.opcode {
+            spirv::Op::SpecConstantTrue => return Ok(SpecializationConstantValue::Bool(true)),
+            spirv::Op::SpecConstantFalse => return Ok(SpecializationConstantValue::Bool(false)),
+            _ => match ty.width() {
+                Some(64) => get_operand_at!(constant, Operand::LiteralBit64, 0)?,
+                _ => u64::from(get_operand_at!(constant, Operand::LiteralBit32, 0)?),
+            },
+        };
+
+        Ok(match ty {
+            ScalarType::Bool => SpecializationConstantValue::Bool(bits != 0),
+            ScalarType::Int { width, signed } => {
+                if width == 0 || width > 64 {
+                    return Err(ReflectError::UnexpectedIntWidth(width));
+                }
+                // Only the low `width` bits of the literal are significant
+                let shift = 64 - width;
+                if signed {
+                    SpecializationConstantValue::Int(((bits << shift) as i64) >> shift)
+                } else {
+                    SpecializationConstantValue::UInt((bits << shift) >> shift)
+                }
+            }
+            ScalarType::Float { width: 16 } => {
+                SpecializationConstantValue::Float(f16_to_f64(bits as u16))
+            }
+            ScalarType::Float { width: 32 } => {
+                SpecializationConstantValue::Float(f64::from(f32::from_bits(bits as u32)))
+            }
+            ScalarType::Float { width: 64 } => {
+                SpecializationConstantValue::Float(f64::from_bits(bits))
+            }
+            ScalarType::Float { width } => return Err(ReflectError::UnexpectedFloatWidth(width)),
+        })
+    }
+
+    /// Evaluates the scalar integer or boolean constant `id` with the default values of all
+    /// specialization constants, including the results of `OpSpecConstantOp` computed from them.
+    ///
+    /// Returns `None` for other types of constants, and for results that depend on operations
+    /// that cannot be evaluated.
+    pub(crate) fn get_default_constant_value(&self, id: u32) -> Result<Option<u64>> {
+        // Constants can only refer to constants declared before them, evaluating them in order
+        // visits every constant once
+        let mut values = BTreeMap::new();
+        for constant in &self.0.types_global_values {
+            let result_id = match constant.result_id {
+                Some(result_id) => result_id,
+                None => continue,
+            };
+            let value = match constant.class.opcode {
+                spirv::Op::ConstantTrue | spirv::Op::SpecConstantTrue => {
+                    Some(DefaultValue::new(1, 1))
+                }
+                spirv::Op::ConstantFalse | spirv::Op::SpecConstantFalse => {
+                    Some(DefaultValue::new(0, 1))
+                }
+                spirv::Op::Constant | spirv::Op::SpecConstant | spirv::Op::SpecConstantOp => {
+                    match self.get_default_value_width(constant)? {
+                        Some(width) if constant.class.opcode == spirv::Op::SpecConstantOp => {
+                            let op = get_operand_at!(
+                                constant,
+                                Operand::LiteralSpecConstantOpInteger,
+                                0
+                            )?;
+                            constant.operands[1..]
+                                .iter()
+                                .map(|operand| match operand {
+                                    Operand::IdRef(id) => values.get(id).copied(),
+                                    _ => None,
+                                })
+                                .collect::<Option<Vec<_>>>()
+                                .and_then(|args| evaluate_spec_constant_op(op, &args))
+                                .map(|bits| DefaultValue::new(bits, width))
+                        }
+                        Some(64) => Some(DefaultValue::new(
+                            get_operand_at!(constant, Operand::LiteralBit64, 0)?,
+                            64,
+                        )),
+                        Some(width) => Some(DefaultValue::new(
+                            u64::from(get_operand_at!(constant, Operand::LiteralBit32, 0)?),
+                            width,
+                        )),
+                        None => None,
+                    }
+                }
+                _ => None,
+            };
+            if result_id == id {
+                return Ok(value.map(|value| value.bits));
+            }
+            if let Some(value) = value {
+                values.insert(result_id, value);
+            }
+        }
+        Err(ReflectError::UnassignedResultId(id))
+    }
+
+    /// Returns the width in bits of the result type of `constant` if it is a scalar integer or
+    /// boolean, `1` for the latter
+    fn get_default_value_width(&self, constant: &Instruction) -> Result<Option<u32>> {
+        let type_id = constant
+            .result_type
+            .ok_or_else(|| ReflectError::MissingResultType(constant.clone()))?;
+        let type_instruction = Self::find_assignment_for(&self.0.types_global_values, type_id)?;
+        Ok(match type_instruction.class.opcode {
+            spirv::Op::TypeBool => Some(1),
+            spirv::Op::TypeInt => {
+                Some(get_operand_at!(type_instruction, Operand::LiteralBit32, 0)?)
+                    .filter(|width| (1..=64).contains(width))
+            }
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,89 @@
+//! Shader stages of entry points
+
+use crate::{EntryPoint, ReflectError, Result};
+use rspirv::spirv;
+
+/// These are bit-exact with `VkShaderStageFlags` in ash and the Vulkan specification,
+/// they're mirrored here to prevent a dependency on ash
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[repr(transparent)]
+pub struct ShaderStageFlags(pub u32);
+
+impl ShaderStageFlags {
+    pub const NONE: Self = Self(0);
+    pub const VERTEX: Self = Self(0x1);
+    pub const TESSELLATION_CONTROL: Self = Self(0x2);
+    pub const TESSELLATION_EVALUATION: Self = Self(0x4);
+    pub const GEOMETRY: Self = Self(0x8);
+    pub const FRAGMENT: Self = Self(0x10);
+    pub const COMPUTE: Self = Self(0x20);
+    pub const ALL_GRAPHICS: Self = Self(0x1f);
+    pub const ALL: Self = Self(0x7fff_ffff);
+
+    pub const TASK_EXT: Self = Self(0x40);
+    pub const MESH_EXT: Self = Self(0x80);
+    pub const RAYGEN_KHR: Self = Self(0x100);
+    pub const ANY_HIT_KHR: Self = Self(0x200);
+    pub const CLOSEST_HIT_KHR: Self = Self(0x400);
+    pub const MISS_KHR: Self = Self(0x800);
+    pub const INTERSECTION_KHR: Self = Self(0x1000);
+    pub const CALLABLE_KHR: Self = Self(0x2000);
+
+    /// Returns the stage an entry point with `execution_model` runs in.
+    ///
+    /// The ray tracing models are shared between `SPV_NV_ray_tracing` and `SPV_KHR_ray_tracing`,
+    /// and the NV task and mesh models map to the same bits as their EXT counterparts.  OpenCL
+    /// kernels have no Vulkan shader stage.
+    pub fn from_execution_model(execution_model: spirv::ExecutionModel) -> Result<Self> {
+        use spirv::ExecutionModel as E;
+        Ok(match execution_model {
+            E::Vertex => Self::VERTEX,
+            E::TessellationControl => Self::TESSELLATION_CONTROL,
+            E::TessellationEvaluation => Self::TESSELLATION_EVALUATION,
+            E::Geometry => Self::GEOMETRY,
+            E::Fragment => Self::FRAGMENT,
+            E::GLCompute => Self::COMPUTE,
+            E::TaskNV | E::TaskEXT => Self::TASK_EXT,
+            E::MeshNV | E::MeshEXT => Self::MESH_EXT,
+            E::RayGenerationNV => Self::RAYGEN_KHR,
+            E::AnyHitNV => Self::ANY_HIT_KHR,
+            E::ClosestHitNV => Self::CLOSEST_HIT_KHR,
+            E::MissNV => Self::MISS_KHR,
+            E::IntersectionNV => Self::INTERSECTION_KHR,
+            E::CallableNV => Self::CALLABLE_KHR,
+            E::Kernel => return Err(ReflectError::UnexpectedExecutionModel(execution_model)),
+        })
+    }
+}
+
+impl_flags!(
+    ShaderStageFlags,
+    [
+        VERTEX,
+        TESSELLATION_CONTROL,
+        TESSELLATION_EVALUATION,
+        GEOMETRY,
+        FRAGMENT,
+        COMPUTE,
+        TASK_EXT,
+        MESH_EXT,
+        RAYGEN_KHR,
+        ANY_HIT_KHR,
+        CLOSEST_HIT_KHR,
+        MISS_KHR,
+        INTERSECTION_KHR,
+        CALLABLE_KHR,
+    ]
+);
+
+impl EntryPoint {
+    /// Returns the shader stage this entry point runs in
+    pub fn stage(&self) -> Result<ShaderStageFlags> {
+        ShaderStageFlags::from_execution_model(self.execution_model)
+    }
+}
@@ -0,0 +1,541 @@
+//! Descriptions of SPIR-V types
+
+use crate::{ReflectError, Reflection, Result};
+use rspirv::dr::{Instruction, Operand};
+use rspirv::spirv;
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::sync::Arc;
+
+/// A scalar type, as declared by `OpTypeBool`, `OpTypeInt` or `OpTypeFloat`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScalarType {
+    Bool,
+    Int { width: u32, signed: bool },
+    Float { width: u32 },
+}
+
+impl ScalarType {
+    /// Width of the scalar in bits, or `None` for booleans which have no defined size
+    pub fn width(&self) -> Option<u32> {
+        match *self {
+            Self::Bool => None,
+            Self::Int { width, .. } | Self::Float { width } => Some(width),
+        }
+    }
+}
+
+/// A scalar, vector or matrix type
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumericType {
+    pub scalar: ScalarType,
+    /// Number of components in a vector, or rows in a matrix. `1` for scalars.
+    pub vector_size: u32,
+    /// Number of columns in a matrix. `1` for scalars and vectors.
+    pub column_count: u32,
+}
+
+impl NumericType {
+    /// Number of interface locations consumed by a single value of this type.
+    ///
+    /// Every location holds four 32-bit components, so 64-bit vectors with more than two
+    /// components consume two locations.  Matrices consume one location per column.
+    pub fn location_count(&self) -> u32 {
+        let locations_per_column = if self.scalar.width() == Some(64) && self.vector_size > 2 {
+            2
+        } else {
+            1
+        };
+        locations_per_column * self.column_count
+    }
+}
+
+/// Properties of an `OpTypeImage`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImageType {
+    /// Type of the components returned by sampling or reading the image
+    pub sampled_type: ScalarType,
+    pub dim: spirv::Dim,
+    /// Whether this is a depth image, `None` if this is not known at compile time
+    pub depth: Option<bool>,
+    pub arrayed: bool,
+    pub multisampled: bool,
+    /// Whether the image is accessed through a sampler (`true`) or as a storage image
+    /// (`false`), `None` if this is only known at runtime
+    pub sampled: Option<bool>,
+    pub format: spirv::ImageFormat,
+}
+
+/// Number of elements in an `OpTypeArray`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ArrayLength {
+    Constant(u32),
+    /// Length provided by a specialization constant
+    SpecConstant {
+        spec_id: u32,
+        /// Length used when the specialization constant is not overridden
+        default: u32,
+    },
+    /// Length computed by an `OpSpecConstantOp` from specialization constants
+    SpecConstantOp {
+        /// Result id of the `OpSpecConstantOp`
+        id: u32,
+        /// Length computed from the default values of the specialization constants, `None` if
+        /// the operation cannot be evaluated
+        default: Option<u32>,
+    },
+}
+
+impl ArrayLength {
+    /// Returns the number of elements unless the array is specialized, `None` if the length is
+    /// computed by an `OpSpecConstantOp` that cannot be evaluated
+    pub fn default_length(&self) -> Option<u32> {
+        match *self {
+            Self::Constant(length)
+            | Self::SpecConstant {
+                default: length, ..
+            } => Some(length),
+            Self::SpecConstantOp { default, .. } => default,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructMember {
+    pub name: String,
+    pub ty: ReflectType,
+    /// Value of the `Offset` decoration, only present on members of externally visible blocks
+    pub offset: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructType {
+    pub name: String,
+    pub members: Vec<StructMember>,
+}
+
+/// An owned description of a SPIR-V `OpType*` instruction and all the types it refers to
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReflectType {
+    Void,
+    Scalar(ScalarType),
+    Vector {
+        scalar: ScalarType,
+        size: u32,
+    },
+    Matrix {
+        scalar: ScalarType,
+        /// Number of components in a column
+        rows: u32,
+        columns: u32,
+    },
+    Array {
+        element: Box<ReflectType>,
+        length: ArrayLength,
+        /// Value of the `ArrayStride` decoration, only present on explicitly laid out arrays
+        stride: Option<u32>,
+    },
+    RuntimeArray {
+        element: Box<ReflectType>,
+        /// Value of the `ArrayStride` decoration, only present on explicitly laid out arrays
+        stride: Option<u32>,
+    },
+    /// A struct, shared between all members and elements of the same struct type
+    Struct(Arc<StructType>),
+    Image(ImageType),
+    Sampler,
+    SampledImage(ImageType),
+    Pointer {
+        storage_class: spirv::StorageClass,
+        /// Type id of the pointee.
+        ///
+        /// Pointee types are not resolved in-place, as `PhysicalStorageBuffer` pointers may
+        /// refer back to the struct that contains them.  Look them up in
+        /// [`Reflection::get_types()`] or resolve them with [`Reflection::get_type()`] instead.
+        pointee: u32,
+    },
+    AccelerationStructure,
+}
+
+impl Reflection {
+    /// Resolves every type declared in the module, keyed by its result id.
+    ///
+    /// Function types and opaque types that cannot back a resource or interface variable are not
+    /// included.  Types are resolved in declaration order, so that the member and element types
+    /// they refer to are resolved once and shared.
+    pub fn get_types(&self) -> Result<BTreeMap<u32, ReflectType>> {
+        let mut types = BTreeMap::new();
+        for instruction in &self.0.types_global_values {
+            if !matches!(
+                instruction.class.opcode,
+                spirv::Op::TypeVoid
+                    | spirv::Op::TypeBool
+                    | spirv::Op::TypeInt
+                    | spirv::Op::TypeFloat
+                    | spirv::Op::TypeVector
+                    | spirv::Op::TypeMatrix
+                    | spirv::Op::TypeArray
+                    | spirv::Op::TypeRuntimeArray
+                    | spirv::Op::TypeStruct
+                    | spirv::Op::TypeImage
+                    | spirv::Op::TypeSampler
+                    | spirv::Op::TypeSampledImage
+                    | spirv::Op::TypePointer
+                    | spirv::Op::TypeAccelerationStructureKHR
+            ) {
+                continue;
+            }
+            let id = instruction
+                .result_id
+                .ok_or_else(|| ReflectError::MissingResultId(instruction.clone()))?;
+            if !types.contains_key(&id) {
+                let ty = self.resolve_type(instruction, &mut types)?;
+                types.insert(id, ty);
+            }
+        }
+        Ok(types)
+    }
+
+    /// Resolves the type declared by `type_id`
+    pub fn get_type(&self, type_id: u32) -> Result<ReflectType> {
+        self.resolve_type(
+            Self::find_assignment_for(&self.0.types_global_values, type_id)?,
+            &mut BTreeMap::new(),
+        )
+    }
+
+    /// Resolves the type of a member or element, reusing it from `types` if it was already
+    /// resolved
+    fn resolve_referenced_type(
+        &self,
+        type_instruction: &Instruction,
+        referenced_type_id: u32,
+        types: &mut BTreeMap<u32, ReflectType>,
+    ) -> Result<ReflectType> {
+        if let Some(ty) = types.get(&referenced_type_id) {
+            return Ok(ty.clone());
+        }
+        let ty = self.resolve_type(
+            self.find_referenced_type(type_instruction, referenced_type_id)?,
+            types,
+        )?;
+        types.insert(referenced_type_id, ty.clone());
+        Ok(ty)
+    }
+
+    /// Resolves an `OpType*` `Instruction`, keeping the types it refers to in `types`
+    fn resolve_type(
+        &self,
+        type_instruction: &Instruction,
+        types: &mut BTreeMap<u32, ReflectType>,
+    ) -> Result<ReflectType> {
+        let type_id = type_instruction
+            .result_id
+            .ok_or_else(|| ReflectError::MissingResultId(type_instruction.clone()))?;
+
+        Ok(match type_instruction.class.opcode {
+            spirv::Op::TypeVoid => ReflectType::Void,
+            spirv::Op::TypeBool | spirv::Op::TypeInt | spirv::Op::TypeFloat => {
+                ReflectType::Scalar(self.get_numeric_type(type_instruction)?.scalar)
+            }
+            spirv::Op::TypeVector => {
+                let ty = self.get_numeric_type(type_instruction)?;
+                ReflectType::Vector {
+                    scalar: ty.scalar,
+                    size: ty.vector_size,
+                }
+            }
+            spirv::Op::TypeMatrix => {
+                let ty = self.get_numeric_type(type_instruction)?;
+                ReflectType::Matrix {
+                    scalar: ty.scalar,
+                    rows: ty.vector_size,
+                    columns: ty.column_count,
+                }
+            }
+            spirv::Op::TypeArray | spirv::Op::TypeRuntimeArray => {
+                let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
+                let element = Box::new(self.resolve_referenced_type(
+                    type_instruction,
+                    element_type_id,
+                    types,
+                )?);
+                let stride =
+                    self.get_decoration_literal(type_id, spirv::Decoration::ArrayStride)?;
+                if type_instruction.class.opcode == spirv::Op::TypeArray {
+                    ReflectType::Array {
+                        element,
+                        length: self.get_array_length_kind(type_instruction)?,
+                        stride,
+                    }
+                } else {
+                    ReflectType::RuntimeArray { element, stride }
+                }
+            }
+            spirv::Op::TypeStruct => {
+                let mut members = vec![];
+                for idx in 0..type_instruction.operands.len() {
+                    let member_type_id = get_operand_at!(type_instruction, Operand::IdRef, idx)?;
+                    let member = idx.try_into()?;
+                    members.push(StructMember {
+                        name: self
+                            .get_member_name(type_id, member)?
+                            .unwrap_or_default()
+                            .to_owned(),
+                        ty: self.resolve_referenced_type(
+                            type_instruction,
+                            member_type_id,
+                            types,
+                        )?,
+                        offset: match self.find_member_decoration(
+                            type_id,
+                            member,
+                            spirv::Decoration::Offset,
+                        )? {
+                            Some(decoration) => {
+                                Some(get_operand_at!(decoration, Operand::LiteralBit32, 3)?)
+                            }
+                            None => None,
+                        },
+                    });
+                }
+                ReflectType::Struct(Arc::new(StructType {
+                    name: self.get_name(type_id)?.unwrap_or_default().to_owned(),
+                    members,
+                }))
+            }
+            spirv::Op::TypeImage => ReflectType::Image(self.get_image_type(type_instruction)?),
+            spirv::Op::TypeSampler => ReflectType::Sampler,
+            spirv::Op::TypeSampledImage => {
+                let image_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
+                let image_instruction =
+                    self.find_referenced_type(type_instruction, image_type_id)?;
+                ReflectType::SampledImage(self.get_image_type(image_instruction)?)
+            }
+            spirv::Op::TypePointer => ReflectType::Pointer {
+                storage_class: get_operand_at!(type_instruction, Operand::StorageClass, 0)?,
+                pointee: get_operand_at!(type_instruction, Operand::IdRef, 1)?,
+            },
+            spirv::Op::TypeAccelerationStructureKHR => ReflectType::AccelerationStructure,
+            _ => {
+                return Err(ReflectError::UnhandledTypeInstruction(
+                    type_instruction.clone(),
+                ))
+            }
+        })
+    }
+
+    /// Resolves an `OpTypeImage` `Instruction` to an [`ImageType`]
+    pub(crate) fn get_image_type(&self, image_instruction: &Instruction) -> Result<ImageType> {
+        if image_instruction.class.opcode != spirv::Op::TypeImage {
+            return Err(ReflectError::UnhandledTypeInstruction(
+                image_instruction.clone(),
+            ));
+        }
+
+        let sampled_type_id = get_operand_at!(image_instruction, Operand::IdRef, 0)?;
+        let sampled_type = self.find_referenced_type(image_instruction, sampled_type_id)?;
+
+        // Depth and Sampled use 2 to signal that this is only known at runtime
+        let tristate = |value| match value {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        };
+
+        Ok(ImageType {
+            sampled_type: self.get_numeric_type(sampled_type)?.scalar,
+            dim: get_operand_at!(image_instruction, Operand::Dim, 1)?,
+            depth: tristate(get_operand_at!(
+                image_instruction,
+                Operand::LiteralBit32,
+                2
+            )?),
+            arrayed: get_operand_at!(image_instruction, Operand::LiteralBit32, 3)? != 0,
+            multisampled: get_operand_at!(image_instruction, Operand::LiteralBit32, 4)? != 0,
+            sampled: match get_operand_at!(image_instruction, Operand::LiteralBit32, 5)? {
+                1 => Some(true),
+                2 => Some(false),
+                _ => None,
+            },
+            format: get_operand_at!(image_instruction, Operand::ImageFormat, 6)?,
+        })
+    }
+
+    /// Returns the [`ArrayLength`] of an `OpTypeArray` `Instruction`, distinguishing
+    /// specialization constants from regular constants
+    pub(crate) fn get_array_length_kind(
+        &self,
+        array_instruction: &Instruction,
+    ) -> Result<ArrayLength> {
+        let num_elements_id = get_operand_at!(array_instruction, Operand::IdRef, 1)?;
+        let num_elements = Self::find_assignment_for(&self.0.types_global_values, num_elements_id)?;
+        match num_elements.class.opcode {
+            spirv::Op::SpecConstantOp => Ok(ArrayLength::SpecConstantOp {
+                id: num_elements_id,
+                default: match self.get_array_length(array_instruction) {
+                    Ok(length) => Some(length),
+                    Err(ReflectError::UnevaluatedArrayLength(_)) => None,
+                    Err(e) => return Err(e),
+                },
+            }),
+            spirv::Op::SpecConstant => {
+                let default = self.get_array_length(array_instruction)?;
+                Ok(
+                    match self.get_decoration_literal(num_elements_id, spirv::Decoration::SpecId)? {
+                        Some(spec_id) => ArrayLength::SpecConstant { spec_id, default },
+                        // Without a SpecId the constant cannot be specialized
+                        None => ArrayLength::Constant(default),
+                    },
+                )
+            }
+            _ => Ok(ArrayLength::Constant(
+                self.get_array_length(array_instruction)?,
+            )),
+        }
+    }
+
+    /// Resolves an `OpTypeBool`, `OpTypeInt`, `OpTypeFloat`, `OpTypeVector` or `OpTypeMatrix`
+    /// `Instruction` to a [`NumericType`]
+    pub(crate) fn get_numeric_type(&self, type_instruction: &Instruction) -> Result<NumericType> {
+        match type_instruction.class.opcode {
+            spirv::Op::TypeBool => Ok(NumericType {
+                scalar: ScalarType::Bool,
+                vector_size: 1,
+                column_count: 1,
+            }),
+            spirv::Op::TypeInt => Ok(NumericType {
+                scalar: ScalarType::Int {
+                    width: get_operand_at!(type_instruction, Operand::LiteralBit32, 0)?,
+                    signed: get_operand_at!(type_instruction, Operand::LiteralBit32, 1)? != 0,
+                },
+                vector_size: 1,
+                column_count: 1,
+            }),
+            spirv::Op::TypeFloat => Ok(NumericType {
+                scalar: ScalarType::Float {
+                    width: get_operand_at!(type_instruction, Operand::LiteralBit32, 0)?,
+                },
+                vector_size: 1,
+                column_count: 1,
+            }),
+            spirv::Op::TypeVector | spirv::Op::TypeMatrix => {
+                let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
+                let count = get_operand_at!(type_instruction, Operand::LiteralBit32, 1)?;
+                let element_type = self.find_referenced_type(type_instruction, element_type_id)?;
+                let element = self.get_numeric_type(element_type)?;
+                // Vectors consist of scalars and matrices of 2 to 4 vectors
+                let valid = if type_instruction.class.opcode == spirv::Op::TypeVector {
+                    element.vector_size == 1 && matches!(count, 2 | 3 | 4 | 8 | 16)
+                } else {
+                    element.vector_size > 1 && element.column_count == 1 && (2..=4).contains(&count)
+                };
+                if !valid {
+                    return Err(ReflectError::UnhandledTypeInstruction(
+                        type_instruction.clone(),
+                    ));
+                }
+                Ok(if type_instruction.class.opcode == spirv::Op::TypeVector {
+                    NumericType {
+                        vector_size: count,
+                        ..element
+                    }
+                } else {
+                    NumericType {
+                        column_count: count,
+                        ..element
+                    }
+                })
+            }
+            _ => Err(ReflectError::UnhandledTypeInstruction(
+                type_instruction.clone(),
+            )),
+        }
+    }
+
+    /// Returns the number of elements in an `OpTypeArray` `Instruction`, evaluating lengths
+    /// computed by an `OpSpecConstantOp` with the default values of specialization constants
+    pub(crate) fn get_array_length(&self, array_instruction: &Instruction) -> Result<u32> {
+        let num_elements_id = get_operand_at!(array_instruction, Operand::IdRef, 1)?;
+        let num_elements = Self::find_assignment_for(&self.0.types_global_values, num_elements_id)?;
+        let num_elements_ty = Self::find_assignment_for(
+            &self.0.types_global_values,
+            num_elements
+                .result_type
+                .ok_or_else(|| ReflectError::MissingResultType(num_elements.clone()))?,
+        )?;
+        if num_elements_ty.class.opcode != spirv::Op::TypeInt {
+            return Err(ReflectError::UnhandledTypeInstruction(
+                num_elements_ty.clone(),
+            ));
+        }
+        // Array size can be any width, any signedness
+        let length = if num_elements.class.opcode == spirv::Op::SpecConstantOp {
+            self.get_default_constant_value(num_elements_id)?
+                .ok_or_else(|| ReflectError::UnevaluatedArrayLength(array_instruction.clone()))?
+                .try_into()?
+        } else {
+            match get_operand_at!(num_elements_ty, Operand::LiteralBit32, 0)? {
+                32 => get_operand_at!(num_elements, Operand::LiteralBit32, 0)?,
+                64 => get_operand_at!(num_elements, Operand::LiteralBit64, 0)?.try_into()?,
+                x => return Err(ReflectError::UnexpectedIntWidth(x)),
+            }
+        };
+        if length == 0 {
+            return Err(ReflectError::ZeroLengthArray(array_instruction.clone()));
+        }
+        Ok(length)
+    }
+
+    /// Resolves `type_id` and strips all `OpTypeArray` levels from it, returning the element type
+    /// and the array dimensions (outermost first)
+    pub(crate) fn peel_arrays(&self, type_id: u32) -> Result<(&Instruction, Vec<u32>)> {
+        let mut type_instruction = Self::find_assignment_for(&self.0.types_global_values, type_id)?;
+        let mut array_dims = vec![];
+        while type_instruction.class.opcode == spirv::Op::TypeArray {
+            array_dims.push(self.get_array_length(type_instruction)?);
+            let element_type_id = get_operand_at!(type_instruction, Operand::IdRef, 0)?;
+            type_instruction = self.find_referenced_type(type_instruction, element_type_id)?;
+        }
+        Ok((type_instruction, array_dims))
+    }
+
+    /// Resolves the type `id` referenced by an operand of `type_instruction`.
+    ///
+    /// Types must be declared before they are referenced, except for struct members referring to
+    /// a pointer declared through `OpTypeForwardPointer`.  Enforcing this guarantees that walking
+    /// a malformed, cyclic type hierarchy terminates.
+    pub(crate) fn find_referenced_type(
+        &self,
+        type_instruction: &Instruction,
+        id: u32,
+    ) -> Result<&Instruction> {
+        for instruction in &self.0.types_global_values {
+            if std::ptr::eq(instruction, type_instruction) {
+                break;
+            }
+            if instruction.result_id == Some(id) {
+                return Ok(instruction);
+            }
+        }
+
+        let instruction = Self::find_assignment_for(&self.0.types_global_values, id)?;
+        if type_instruction.class.opcode == spirv::Op::TypeStruct
+            && instruction.class.opcode == spirv::Op::TypePointer
+        {
+            Ok(instruction)
+        } else {
+            Err(ReflectError::ForwardTypeReference(
+                type_instruction.clone(),
+                id,
+            ))
+        }
+    }
+}
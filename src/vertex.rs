@@ -0,0 +1,230 @@
+//! Reflection of vertex input attributes
+
+use crate::{EntryPoint, Format, NumericType, ReflectError, Reflection, Result, ScalarType};
+use rspirv::spirv;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VertexInputAttribute {
+    pub location: u32,
+    pub format: Format,
+    /// Size in bytes of the attribute as described by `format`
+    pub size: u32,
+    /// Debug name of the input variable this attribute belongs to.
+    ///
+    /// Matrices and arrays are split into one attribute per column and array element, which all
+    /// share the name of the input variable.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VertexInputAttributeLayout {
+    pub location: u32,
+    pub format: Format,
+    /// Offset in bytes from the start of the vertex
+    pub offset: u32,
+}
+
+/// Layout of a single interleaved vertex buffer binding
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VertexInputLayout {
+    pub attributes: Vec<VertexInputAttributeLayout>,
+    /// Distance in bytes between two consecutive vertices
+    pub stride: u32,
+}
+
+impl VertexInputLayout {
+    /// Packs `attributes` tightly into a single interleaved vertex, in the order they are given
+    pub fn packed(attributes: &[VertexInputAttribute]) -> Self {
+        let mut stride = 0;
+        let attributes = attributes
+            .iter()
+            .map(|attribute| {
+                let offset = stride;
+                stride += attribute.size;
+                VertexInputAttributeLayout {
+                    location: attribute.location,
+                    format: attribute.format,
+                    offset,
+                }
+            })
+            .collect();
+
+        Self { attributes, stride }
+    }
+}
+
+/// Returns the format of a single column of `ty`
+fn column_format(ty: &NumericType) -> Option<Format> {
+    let formats: [Format; 4] = match ty.scalar {
+        ScalarType::Int {
+            width: 8,
+            signed: false,
+        } => [
+            Format::R8_UINT,
+            Format::R8G8_UINT,
+            Format::R8G8B8_UINT,
+            Format::R8G8B8A8_UINT,
+        ],
+        ScalarType::Int {
+            width: 8,
+            signed: true,
+        } => [
+            Format::R8_SINT,
+            Format::R8G8_SINT,
+            Format::R8G8B8_SINT,
+            Format::R8G8B8A8_SINT,
+        ],
+        ScalarType::Int {
+            width: 16,
+            signed: false,
+        } => [
+            Format::R16_UINT,
+            Format::R16G16_UINT,
+            Format::R16G16B16_UINT,
+            Format::R16G16B16A16_UINT,
+        ],
+        ScalarType::Int {
+            width: 16,
+            signed: true,
+        } => [
+            Format::R16_SINT,
+            Format::R16G16_SINT,
+            Format::R16G16B16_SINT,
+            Format::R16G16B16A16_SINT,
+        ],
+        ScalarType::Float { width: 16 } => [
+            Format::R16_SFLOAT,
+            Format::R16G16_SFLOAT,
+            Format::R16G16B16_SFLOAT,
+            Format::R16G16B16A16_SFLOAT,
+        ],
+        ScalarType::Int {
+            width: 32,
+            signed: false,
+        } => [
+            Format::R32_UINT,
+            Format::R32G32_UINT,
+            Format::R32G32B32_UINT,
+            Format::R32G32B32A32_UINT,
+        ],
+        ScalarType::Int {
+            width: 32,
+            signed: true,
+        } => [
+            Format::R32_SINT,
+            Format::R32G32_SINT,
+            Format::R32G32B32_SINT,
+            Format::R32G32B32A32_SINT,
+        ],
+        ScalarType::Float { width: 32 } => [
+            Format::R32_SFLOAT,
+            Format::R32G32_SFLOAT,
+            Format::R32G32B32_SFLOAT,
+            Format::R32G32B32A32_SFLOAT,
+        ],
+        ScalarType::Int {
+            width: 64,
+            signed: false,
+        } => [
+            Format::R64_UINT,
+            Format::R64G64_UINT,
+            Format::R64G64B64_UINT,
+            Format::R64G64B64A64_UINT,
+        ],
+        ScalarType::Int {
+            width: 64,
+            signed: true,
+        } => [
+            Format::R64_SINT,
+            Format::R64G64_SINT,
+            Format::R64G64B64_SINT,
+            Format::R64G64B64A64_SINT,
+        ],
+        ScalarType::Float { width: 64 } => [
+            Format::R64_SFLOAT,
+            Format::R64G64_SFLOAT,
+            Format::R64G64B64_SFLOAT,
+            Format::R64G64B64A64_SFLOAT,
+        ],
+        _ => return None,
+    };
+
+    formats
+        .get(ty.vector_size.checked_sub(1)? as usize)
+        .copied()
+}
+
+/// Bound on vertex input locations, far above the `maxVertexInputAttributes` that Vulkan
+/// implementations report.  Attributes are reflected per location, and an input array would
+/// otherwise make up billions of them.
+const MAX_VERTEX_INPUT_LOCATIONS: u32 = 4096;
+
+impl Reflection {
+    /// Returns the vertex input attributes of a vertex shader `entry_point`, sorted by location.
+    ///
+    /// Builtin inputs such as `gl_VertexIndex` are not part of the vertex input state and are
+    /// skipped.
+    pub fn get_vertex_input_attributes(
+        &self,
+        entry_point: &EntryPoint,
+    ) -> Result<Vec<VertexInputAttribute>> {
+        if entry_point.execution_model != spirv::ExecutionModel::Vertex {
+            return Err(ReflectError::UnexpectedExecutionModel(
+                entry_point.execution_model,
+            ));
+        }
+
+        let mut attributes = vec![];
+
+        for variable in self.get_interface_variables(entry_point)? {
+            if variable.storage_class != spirv::StorageClass::Input || variable.is_builtin() {
+                continue;
+            }
+
+            let location = variable
+                .location
+                .ok_or_else(|| ReflectError::MissingLocationDecoration(variable.name.clone()))?;
+            let format = column_format(&variable.ty)
+                .ok_or(ReflectError::UnsupportedVertexInputType(variable.ty))?;
+            let size = variable.ty.scalar.width().unwrap_or(0) / 8 * variable.ty.vector_size;
+
+            let columns = variable
+                .array_dims
+                .iter()
+                .try_fold(variable.ty.column_count, |count, &dim| {
+                    count.checked_mul(dim)
+                });
+            // 64-bit three and four component vectors consume two locations
+            let locations_per_column = variable.ty.location_count() / variable.ty.column_count;
+            let columns = columns
+                .filter(|columns| {
+                    columns
+                        .checked_mul(locations_per_column)
+                        .and_then(|count| location.checked_add(count))
+                        .is_some_and(|end| end <= MAX_VERTEX_INPUT_LOCATIONS)
+                })
+                .ok_or_else(|| {
+                    ReflectError::TooManyVertexInputLocations(
+                        variable.name.clone(),
+                        MAX_VERTEX_INPUT_LOCATIONS,
+                    )
+                })?;
+
+            for column in 0..columns {
+                attributes.push(VertexInputAttribute {
+                    location: location + column * locations_per_column,
+                    format,
+                    size,
+                    name: variable.name.clone(),
+                });
+            }
+        }
+
+        attributes.sort_by_key(|attribute| attribute.location);
+
+        Ok(attributes)
+    }
+}
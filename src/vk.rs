@@ -0,0 +1,99 @@
+//! Conversions into the Vulkan structures of ash
+
+use crate::{BindingCount, DescriptorInfo, DescriptorType, PushConstantInfo, ShaderStageFlags};
+use ash::vk;
+use std::collections::BTreeMap;
+
+impl From<DescriptorType> for vk::DescriptorType {
+    fn from(ty: DescriptorType) -> Self {
+        Self::from_raw(ty.0 as i32)
+    }
+}
+
+impl From<ShaderStageFlags> for vk::ShaderStageFlags {
+    fn from(stages: ShaderStageFlags) -> Self {
+        Self::from_raw(stages.0)
+    }
+}
+
+impl From<PushConstantInfo> for vk::PushConstantRange {
+    fn from(info: PushConstantInfo) -> Self {
+        Self {
+            stage_flags: info.stages.into(),
+            offset: info.offset,
+            size: info.size,
+        }
+    }
+}
+
+impl DescriptorInfo {
+    /// Returns the `VkDescriptorSetLayoutBinding` declaring this descriptor at `binding`.
+    ///
+    /// [`BindingCount::Unbounded`] arrays get a `descriptorCount` of `max_unbounded_count`, the
+    /// upper bound for the count passed in `VkDescriptorSetVariableDescriptorCountAllocateInfo`.
+    pub fn to_vk_binding(
+        &self,
+        binding: u32,
+        max_unbounded_count: u32,
+    ) -> vk::DescriptorSetLayoutBinding<'static> {
+        vk::DescriptorSetLayoutBinding::default()
+            .binding(binding)
+            .descriptor_type(self.ty.into())
+            .descriptor_count(match self.binding_count {
+                BindingCount::One => 1,
+                // Array lengths are read from 32-bit literals
+                BindingCount::StaticSized(count) => count as u32,
+                BindingCount::Unbounded => max_unbounded_count,
+            })
+            .stage_flags(self.stages.into())
+    }
+
+    /// Returns the `VkDescriptorBindingFlags` needed to declare this descriptor, which is
+    /// `PARTIALLY_BOUND` for [`BindingCount::Unbounded`] arrays and empty otherwise.
+    ///
+    /// `VARIABLE_DESCRIPTOR_COUNT` is not included, as Vulkan only allows it on the highest
+    /// binding of a set.  [`vk_descriptor_binding_flags()`] adds it where it applies.
+    pub fn to_vk_binding_flags(&self) -> vk::DescriptorBindingFlags {
+        match self.binding_count {
+            BindingCount::Unbounded => vk::DescriptorBindingFlags::PARTIALLY_BOUND,
+            _ => vk::DescriptorBindingFlags::empty(),
+        }
+    }
+}
+
+/// Converts a single set returned by [`crate::Reflection::get_descriptor_sets()`] into the
+/// `pBindings` of a `VkDescriptorSetLayoutCreateInfo`, sorted by binding.
+///
+/// See [`DescriptorInfo::to_vk_binding()`] for the meaning of `max_unbounded_count`.
+pub fn vk_descriptor_set_layout_bindings(
+    bindings: &BTreeMap<u32, DescriptorInfo>,
+    max_unbounded_count: u32,
+) -> Vec<vk::DescriptorSetLayoutBinding<'static>> {
+    bindings
+        .iter()
+        .map(|(&binding, info)| info.to_vk_binding(binding, max_unbounded_count))
+        .collect()
+}
+
+/// Converts a single set returned by [`crate::Reflection::get_descriptor_sets()`] into the
+/// `pBindingFlags` of a `VkDescriptorSetLayoutBindingFlagsCreateInfo`, in the same order as
+/// [`vk_descriptor_set_layout_bindings()`].
+///
+/// [`BindingCount::Unbounded`] arrays are `PARTIALLY_BOUND`, and the highest binding of the set
+/// is also `VARIABLE_DESCRIPTOR_COUNT` if it is unbounded.  Vulkan only allows a variable count on
+/// the highest binding, so unbounded arrays at lower bindings always hold the
+/// `max_unbounded_count` passed to [`vk_descriptor_set_layout_bindings()`].
+pub fn vk_descriptor_binding_flags(
+    bindings: &BTreeMap<u32, DescriptorInfo>,
+) -> Vec<vk::DescriptorBindingFlags> {
+    let mut flags = bindings
+        .values()
+        .map(DescriptorInfo::to_vk_binding_flags)
+        .collect::<Vec<_>>();
+    if let (Some((_, info)), Some(last)) = (bindings.iter().next_back(), flags.last_mut()) {
+        if info.binding_count == BindingCount::Unbounded {
+            *last |= vk::DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT;
+        }
+    }
+    flags
+}
@@ -0,0 +1,282 @@
+//! Conversions into the bind group layout entries of wgpu
+
+use crate::{
+    AccessFlags, ArrayLength, BindingCount, DescriptorInfo, DescriptorType, ImageType,
+    ReflectError, Result, SamplingFlags, ScalarType, ShaderStageFlags, StructLayout, TypeLayout,
+};
+use rspirv::spirv;
+use std::collections::{BTreeMap, HashSet};
+use std::num::{NonZeroU32, NonZeroU64};
+use std::sync::Arc;
+use wgpu_types as wgt;
+
+impl ShaderStageFlags {
+    /// Returns the wgpu `ShaderStages` for these stages, `None` if any of them does not exist in
+    /// wgpu (ie. tessellation, geometry, intersection and callable shaders)
+    pub fn to_wgpu(self) -> Option<wgt::ShaderStages> {
+        let mut stages = wgt::ShaderStages::NONE;
+        let mut remaining = self;
+        for (flag, stage) in [
+            (Self::VERTEX, wgt::ShaderStages::VERTEX),
+            (Self::FRAGMENT, wgt::ShaderStages::FRAGMENT),
+            (Self::COMPUTE, wgt::ShaderStages::COMPUTE),
+            (Self::TASK_EXT, wgt::ShaderStages::TASK),
+            (Self::MESH_EXT, wgt::ShaderStages::MESH),
+            (Self::RAYGEN_KHR, wgt::ShaderStages::RAY_GENERATION),
+            (Self::ANY_HIT_KHR, wgt::ShaderStages::ANY_HIT),
+            (Self::CLOSEST_HIT_KHR, wgt::ShaderStages::CLOSEST_HIT),
+            (Self::MISS_KHR, wgt::ShaderStages::MISS),
+        ] {
+            if self.contains(flag) {
+                stages |= stage;
+                remaining &= !flag;
+            }
+        }
+        remaining.is_empty().then_some(stages)
+    }
+}
+
+/// Whether the size of `ty` depends on specialization constants, visiting every distinct nested
+/// struct once
+fn is_specialized(ty: &TypeLayout, visited: &mut HashSet<*const StructLayout>) -> bool {
+    match ty {
+        TypeLayout::Array {
+            element, length, ..
+        } => {
+            matches!(
+                length,
+                Some(ArrayLength::SpecConstant { .. } | ArrayLength::SpecConstantOp { .. })
+            ) || is_specialized(element, visited)
+        }
+        TypeLayout::Struct(layout) => {
+            visited.insert(Arc::as_ptr(layout))
+                && layout
+                    .members
+                    .iter()
+                    .any(|member| is_specialized(&member.ty, visited))
+        }
+        TypeLayout::Numeric(_) | TypeLayout::Matrix { .. } | TypeLayout::PhysicalPointer => false,
+    }
+}
+
+/// Returns the wgpu `TextureFormat` of a storage image format, `None` for `Unknown` and formats
+/// that wgpu does not support
+fn wgpu_texture_format(format: spirv::ImageFormat) -> Option<wgt::TextureFormat> {
+    use spirv::ImageFormat as F;
+    use wgt::TextureFormat as T;
+    Some(match format {
+        F::Rgba32f => T::Rgba32Float,
+        F::Rgba16f => T::Rgba16Float,
+        F::R32f => T::R32Float,
+        F::Rgba8 => T::Rgba8Unorm,
+        F::Rgba8Snorm => T::Rgba8Snorm,
+        F::Rg32f => T::Rg32Float,
+        F::Rg16f => T::Rg16Float,
+        F::R11fG11fB10f => T::Rg11b10Ufloat,
+        F::R16f => T::R16Float,
+        F::Rgba16 => T::Rgba16Unorm,
+        F::Rgb10A2 => T::Rgb10a2Unorm,
+        F::Rg16 => T::Rg16Unorm,
+        F::Rg8 => T::Rg8Unorm,
+        F::R16 => T::R16Unorm,
+        F::R8 => T::R8Unorm,
+        F::Rgba16Snorm => T::Rgba16Snorm,
+        F::Rg16Snorm => T::Rg16Snorm,
+        F::Rg8Snorm => T::Rg8Snorm,
+        F::R16Snorm => T::R16Snorm,
+        F::R8Snorm => T::R8Snorm,
+        F::Rgba32i => T::Rgba32Sint,
+        F::Rgba16i => T::Rgba16Sint,
+        F::Rgba8i => T::Rgba8Sint,
+        F::R32i => T::R32Sint,
+        F::Rg32i => T::Rg32Sint,
+        F::Rg16i => T::Rg16Sint,
+        F::Rg8i => T::Rg8Sint,
+        F::R16i => T::R16Sint,
+        F::R8i => T::R8Sint,
+        F::Rgba32ui => T::Rgba32Uint,
+        F::Rgba16ui => T::Rgba16Uint,
+        F::Rgba8ui => T::Rgba8Uint,
+        F::R32ui => T::R32Uint,
+        F::Rgb10a2ui => T::Rgb10a2Uint,
+        F::Rg32ui => T::Rg32Uint,
+        F::Rg16ui => T::Rg16Uint,
+        F::Rg8ui => T::Rg8Uint,
+        F::R16ui => T::R16Uint,
+        F::R8ui => T::R8Uint,
+        F::R64ui => T::R64Uint,
+        F::R64i | F::Unknown => return None,
+    })
+}
+
+/// Returns the `TextureViewDimension` of `image`, `None` for dimensions without a wgpu view
+/// (ie. 1D arrays, buffers, rectangles and subpass data)
+fn wgpu_view_dimension(image: &ImageType) -> Option<wgt::TextureViewDimension> {
+    use wgt::TextureViewDimension as V;
+    Some(match (image.dim, image.arrayed) {
+        (spirv::Dim::Dim1D, false) => V::D1,
+        (spirv::Dim::Dim2D, false) => V::D2,
+        (spirv::Dim::Dim2D, true) => V::D2Array,
+        (spirv::Dim::Dim3D, false) => V::D3,
+        (spirv::Dim::DimCube, false) => V::Cube,
+        (spirv::Dim::DimCube, true) => V::CubeArray,
+        _ => return None,
+    })
+}
+
+impl DescriptorInfo {
+    /// Returns the wgpu `BindGroupLayoutEntry` declaring this descriptor at `binding`.
+    ///
+    /// [`BindingCount::Unbounded`] arrays get a `count` of `max_unbounded_count`.  Samplers that
+    /// are only used for depth comparisons are declared as `Comparison` samplers and all other
+    /// samplers as `Filtering`.  Sampled images use the `Depth` sample type if they are sampled
+    /// with a depth reference or their [`ImageType::depth`] is known to be `true`, see
+    /// [`DescriptorInfo::sampling`].
+    ///
+    /// Fails with [`ReflectError::UnsupportedWgpuBinding`] for descriptors that cannot be
+    /// expressed in wgpu, such as combined image samplers, texel buffers, input attachments and
+    /// storage images without a format.
+    pub fn to_wgpu_entry(
+        &self,
+        binding: u32,
+        max_unbounded_count: NonZeroU32,
+    ) -> Result<wgt::BindGroupLayoutEntry> {
+        let unsupported = |reason: String| ReflectError::UnsupportedWgpuBinding(binding, reason);
+        let image = || {
+            self.image
+                .as_ref()
+                .ok_or_else(|| unsupported(format!("{:?} without image type", self.ty)))
+        };
+        let view_dimension = |image: &ImageType| {
+            wgpu_view_dimension(image).ok_or_else(|| {
+                unsupported(format!(
+                    "{:?} image (arrayed: {})",
+                    image.dim, image.arrayed
+                ))
+            })
+        };
+
+        let ty = match self.ty {
+            DescriptorType::SAMPLER => wgt::BindingType::Sampler(match self.sampling {
+                SamplingFlags::COMPARE => wgt::SamplerBindingType::Comparison,
+                sampling if sampling.contains(SamplingFlags::COMPARE) => {
+                    return Err(unsupported(
+                        "sampler used both with and without depth comparison".to_owned(),
+                    ))
+                }
+                _ => wgt::SamplerBindingType::Filtering,
+            }),
+            DescriptorType::SAMPLED_IMAGE => {
+                let image = image()?;
+                let sample_type = match image.sampled_type {
+                    _ if image.depth == Some(true)
+                        || self.sampling.contains(SamplingFlags::COMPARE) =>
+                    {
+                        wgt::TextureSampleType::Depth
+                    }
+                    ScalarType::Int { signed: true, .. } => wgt::TextureSampleType::Sint,
+                    ScalarType::Int { signed: false, .. } => wgt::TextureSampleType::Uint,
+                    // Multisampled textures can only be loaded from, never filtered
+                    _ => wgt::TextureSampleType::Float {
+                        filterable: !image.multisampled,
+                    },
+                };
+                wgt::BindingType::Texture {
+                    sample_type,
+                    view_dimension: view_dimension(image)?,
+                    multisampled: image.multisampled,
+                }
+            }
+            DescriptorType::STORAGE_IMAGE => {
+                let image = image()?;
+                let access = if self.used_access.contains(AccessFlags::ATOMIC) {
+                    wgt::StorageTextureAccess::Atomic
+                } else if !self.access.contains(AccessFlags::WRITE) {
+                    wgt::StorageTextureAccess::ReadOnly
+                } else if !self.access.contains(AccessFlags::READ) {
+                    wgt::StorageTextureAccess::WriteOnly
+                } else {
+                    wgt::StorageTextureAccess::ReadWrite
+                };
+                wgt::BindingType::StorageTexture {
+                    access,
+                    format: wgpu_texture_format(image.format)
+                        .ok_or_else(|| unsupported(format!("{:?} format", image.format)))?,
+                    view_dimension: view_dimension(image)?,
+                }
+            }
+            DescriptorType::UNIFORM_BUFFER
+            | DescriptorType::STORAGE_BUFFER
+            | DescriptorType::UNIFORM_BUFFER_DYNAMIC
+            | DescriptorType::STORAGE_BUFFER_DYNAMIC => wgt::BindingType::Buffer {
+                ty: match self.ty {
+                    DescriptorType::UNIFORM_BUFFER | DescriptorType::UNIFORM_BUFFER_DYNAMIC => {
+                        wgt::BufferBindingType::Uniform
+                    }
+                    _ => wgt::BufferBindingType::Storage {
+                        read_only: !self.access.contains(AccessFlags::WRITE),
+                    },
+                },
+                has_dynamic_offset: matches!(
+                    self.ty,
+                    DescriptorType::UNIFORM_BUFFER_DYNAMIC | DescriptorType::STORAGE_BUFFER_DYNAMIC
+                ),
+                // wgpu validates against the size of the block with a single element in its
+                // trailing runtime array.  Blocks sized by specialization constants are only
+                // validated at draw time, as their default size may not be the one in use.
+                min_binding_size: self
+                    .block
+                    .as_ref()
+                    .filter(|block| {
+                        let mut visited = HashSet::new();
+                        !block
+                            .members
+                            .iter()
+                            .any(|member| is_specialized(&member.ty, &mut visited))
+                    })
+                    .and_then(|block| {
+                        NonZeroU64::new(
+                            u64::from(block.size)
+                                + u64::from(block.runtime_array_stride().unwrap_or(0)),
+                        )
+                    }),
+            },
+            DescriptorType::ACCELERATION_STRUCTURE_KHR
+            | DescriptorType::ACCELERATION_STRUCTURE_NV => {
+                wgt::BindingType::AccelerationStructure {
+                    vertex_return: false,
+                }
+            }
+            ty => return Err(unsupported(format!("{:?} descriptor", ty))),
+        };
+
+        Ok(wgt::BindGroupLayoutEntry {
+            binding,
+            visibility: self
+                .stages
+                .to_wgpu()
+                .ok_or_else(|| unsupported(format!("{:?} stages", self.stages)))?,
+            ty,
+            count: match self.binding_count {
+                BindingCount::One => None,
+                // Array lengths are read from 32-bit literals and are never zero
+                BindingCount::StaticSized(count) => NonZeroU32::new(count as u32),
+                BindingCount::Unbounded => Some(max_unbounded_count),
+            },
+        })
+    }
+}
+
+/// Converts a single set returned by [`crate::Reflection::get_descriptor_sets()`] into the
+/// `entries` of a wgpu `BindGroupLayoutDescriptor`, sorted by binding.
+///
+/// See [`DescriptorInfo::to_wgpu_entry()`] for the meaning of `max_unbounded_count`.
+pub fn wgpu_bind_group_layout_entries(
+    bindings: &BTreeMap<u32, DescriptorInfo>,
+    max_unbounded_count: NonZeroU32,
+) -> Result<Vec<wgt::BindGroupLayoutEntry>> {
+    bindings
+        .iter()
+        .map(|(&binding, info)| info.to_wgpu_entry(binding, max_unbounded_count))
+        .collect()
+}
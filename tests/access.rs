@@ -0,0 +1,11 @@
+use rspirv_reflect::*;
+
+#[test]
+fn access_flags_debug() {
+    assert_eq!(format!("{:?}", AccessFlags::NONE), "NONE");
+    assert_eq!(
+        format!("{:?}", AccessFlags::READ_WRITE | AccessFlags::ATOMIC),
+        "READ | WRITE | ATOMIC"
+    );
+    assert_eq!(format!("{:?}", AccessFlags(0x10 | 1)), "READ | 0x10");
+}
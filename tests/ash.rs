@@ -0,0 +1,72 @@
+#![cfg(feature = "ash")]
+
+use ash::vk;
+use rspirv_reflect::*;
+
+#[test]
+fn descriptor_set_layout_bindings() {
+    let spirv = include_bytes!("shader-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    let bindings = vk_descriptor_set_layout_bindings(&sets[&6], 1024);
+    assert_eq!(
+        bindings
+            .iter()
+            .map(|b| (b.binding, b.descriptor_type, b.descriptor_count))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, vk::DescriptorType::STORAGE_TEXEL_BUFFER, 1),
+            (1, vk::DescriptorType::UNIFORM_TEXEL_BUFFER, 1),
+            (2, vk::DescriptorType::STORAGE_BUFFER, 1024),
+            (3, vk::DescriptorType::STORAGE_BUFFER, 1),
+        ]
+    );
+    assert_eq!(bindings[2].stage_flags, vk::ShaderStageFlags::COMPUTE);
+
+    // Binding 2 is unbounded, but not the highest binding of the set
+    assert_eq!(
+        vk_descriptor_binding_flags(&sets[&6]),
+        vec![
+            vk::DescriptorBindingFlags::empty(),
+            vk::DescriptorBindingFlags::empty(),
+            vk::DescriptorBindingFlags::PARTIALLY_BOUND,
+            vk::DescriptorBindingFlags::empty(),
+        ]
+    );
+
+    let spirv = include_bytes!("shader_cs-hlsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let hlsl_sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    // The only binding of the set is also its highest
+    assert_eq!(
+        vk_descriptor_binding_flags(&hlsl_sets[&1]),
+        vec![
+            vk::DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT
+                | vk::DescriptorBindingFlags::PARTIALLY_BOUND
+        ]
+    );
+
+    let binding = sets[&3][&0].to_vk_binding(0, 1024);
+    assert_eq!(binding.descriptor_count, 10);
+    assert!(binding.p_immutable_samplers.is_null());
+}
+
+#[test]
+fn push_constant_range() {
+    let spirv = include_bytes!("push_constants_ps-hlsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let range = vk::PushConstantRange::from(reflect.get_push_constant_range().unwrap().unwrap());
+    assert_eq!(range.stage_flags, vk::ShaderStageFlags::FRAGMENT);
+    assert_eq!(range.offset, 0);
+    assert_eq!(range.size, 404);
+}
@@ -0,0 +1,74 @@
+use rspirv_reflect::*;
+
+#[test]
+fn descriptor_indexing() {
+    let spirv = include_bytes!("shader-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    assert_eq!(
+        reflect.get_capabilities().unwrap(),
+        vec![
+            spirv::Capability::Shader,
+            spirv::Capability::SampledBuffer,
+            spirv::Capability::ImageBuffer,
+            spirv::Capability::RuntimeDescriptorArray,
+        ]
+    );
+    assert_eq!(
+        reflect.get_extensions().unwrap(),
+        vec!["SPV_EXT_descriptor_indexing".to_owned()]
+    );
+    assert_eq!(
+        reflect.get_extended_instruction_sets().unwrap(),
+        vec!["GLSL.std.450".to_owned()]
+    );
+
+    let requirements = reflect.get_vulkan_requirements().unwrap();
+    assert_eq!(requirements.api_version, (1, 0));
+    assert_eq!(
+        requirements
+            .features
+            .iter()
+            .map(|f| f.name.as_ref())
+            .collect::<Vec<_>>(),
+        vec!["descriptorIndexing", "runtimeDescriptorArray"]
+    );
+
+    let extension = VulkanExtension {
+        name: "VK_EXT_descriptor_indexing".into(),
+        promoted_to: Some((1, 2)),
+    };
+    assert_eq!(
+        requirements.extensions.iter().collect::<Vec<_>>(),
+        vec![&extension]
+    );
+    assert!(!extension.is_core_in((1, 1)));
+    assert!(extension.is_core_in((1, 3)));
+
+    assert!(requirements.unknown_capabilities.is_empty());
+    assert!(requirements.unknown_extensions.is_empty());
+}
+
+#[test]
+fn core_features() {
+    let spirv = include_bytes!("push_constants_ps-hlsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let requirements = reflect.get_vulkan_requirements().unwrap();
+    assert_eq!(
+        requirements.features.into_iter().collect::<Vec<_>>(),
+        vec![
+            VulkanFeature {
+                structure: "VkPhysicalDeviceFeatures".into(),
+                name: "shaderFloat64".into(),
+            },
+            VulkanFeature {
+                structure: "VkPhysicalDeviceFeatures".into(),
+                name: "shaderInt64".into(),
+            },
+        ]
+    );
+    assert!(requirements.extensions.is_empty());
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Runs the `rspirv-reflect` binary from the crate root
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rspirv-reflect"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("Failed to run rspirv-reflect")
+}
+
+/// Returns the paths of all compiled shaders, relative to the crate root
+fn compiled_shaders() -> Vec<String> {
+    let tests = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let mut paths = std::fs::read_dir(tests)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.ends_with(".spv"))
+        .map(|name| format!("tests/{}", name))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn table() {
+    let output = run(&["tests/entry_points_spirv1_4-glsl.spv"]);
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "\
+tests/entry_points_spirv1_4-glsl.spv
+  Entry points:
+    name    execution model  workgroup size
+    main_a  GLCompute        8x8x1
+    main_b  GLCompute        64x1x1
+  Descriptor sets:
+    set  binding  type            count  name       stages   access        used
+    0    0        STORAGE_BUFFER  1      g_bufferA  COMPUTE  READ | WRITE  WRITE
+    0    1        STORAGE_BUFFER  1      g_bufferB  COMPUTE  READ | WRITE  WRITE
+  Push constants:
+    entry point  offset  size  stages
+    main_a       0       4     COMPUTE
+    main_b       0       12    COMPUTE
+"
+    );
+}
+
+#[test]
+fn json() {
+    let shaders = compiled_shaders();
+    let args = std::iter::once("--json")
+        .chain(shaders.iter().map(String::as_str))
+        .collect::<Vec<_>>();
+    let output = run(&args);
+    assert!(output.status.success());
+
+    let reports: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let reports = reports.as_array().unwrap();
+    assert_eq!(reports.len(), shaders.len());
+    for (report, shader) in reports.iter().zip(&shaders) {
+        assert_eq!(report["file"], shader.as_str());
+        assert!(!report["entry_points"].as_array().unwrap().is_empty());
+    }
+
+    let report = &reports[shaders
+        .iter()
+        .position(|shader| shader == "tests/push_constants_ps-hlsl.spv")
+        .unwrap()];
+    assert_eq!(report["entry_points"][0]["name"], "main");
+    assert_eq!(report["entry_points"][0]["push_constants"]["size"], 404);
+}
+
+#[test]
+fn check() {
+    let output = run(&[
+        "--check",
+        "tests/shader-glsl.spv",
+        "tests/shader_cs-hlsl.spv",
+    ]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+
+    // Errors are reported for every file that fails, while the others are still reflected
+    let output = run(&["--check", "tests/shader.comp", "tests/shader-glsl.spv"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.lines().count(), 1);
+    assert!(stderr.starts_with("tests/shader.comp: "));
+
+    let output = run(&["tests/shader.comp", "tests/shader-glsl.spv"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("tests/shader-glsl.spv\n"));
+}
@@ -0,0 +1,22 @@
+#version 450
+
+// `Light` is padded from 20 to 32 bytes by the std140 array stride
+struct Light
+{
+    vec3 position;
+    float type;
+    float intensity;
+};
+
+layout(set = 0, binding = 0) uniform Scene
+{
+    mat3 view;
+    Light lights[2];
+    float exposure;
+}
+scene;
+
+void main()
+{
+    gl_Position = vec4(scene.view * scene.lights[1].position * scene.exposure, 1.0);
+}
@@ -0,0 +1,20 @@
+/// Layout of `LargeArrays`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LargeArrays {
+    pub count: u32,
+    pub _padding0: [u8; 136],
+    pub weights: [f32; 40],
+    pub _padding1: [u8; 4],
+    pub indices: [[u32; 2]; 100],
+}
+
+impl LargeArrays {
+    /// Size of the block in bytes, excluding the padding at the end of this struct
+    pub const SIZE: usize = 1104;
+}
+
+const _: () = assert!(::std::mem::size_of::<LargeArrays>() == 1104);
+const _: () = assert!(::std::mem::offset_of!(LargeArrays, count) == 0);
+const _: () = assert!(::std::mem::offset_of!(LargeArrays, weights) == 140);
+const _: () = assert!(::std::mem::offset_of!(LargeArrays, indices) == 304);
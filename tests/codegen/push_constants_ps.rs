@@ -0,0 +1,28 @@
+/// Layout of `type.PushConstant.PushConstant`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PushConstants {
+    pub a: u32,
+    pub b: f32,
+    pub c: u32,
+    pub _padding0: [u8; 4],
+    pub d: u64,
+    pub e: [f64; 6],
+    pub f: [[[f32; 4]; 4]; 5],
+    pub g: [i32; 3],
+    pub _padding1: [u8; 4],
+}
+
+impl PushConstants {
+    /// Size of the block in bytes, excluding the padding at the end of this struct
+    pub const SIZE: usize = 404;
+}
+
+const _: () = assert!(::std::mem::size_of::<PushConstants>() == 408);
+const _: () = assert!(::std::mem::offset_of!(PushConstants, a) == 0);
+const _: () = assert!(::std::mem::offset_of!(PushConstants, b) == 4);
+const _: () = assert!(::std::mem::offset_of!(PushConstants, c) == 8);
+const _: () = assert!(::std::mem::offset_of!(PushConstants, d) == 16);
+const _: () = assert!(::std::mem::offset_of!(PushConstants, e) == 24);
+const _: () = assert!(::std::mem::offset_of!(PushConstants, f) == 72);
+const _: () = assert!(::std::mem::offset_of!(PushConstants, g) == 392);
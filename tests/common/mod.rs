@@ -0,0 +1,55 @@
+//! Hand-written modules for cases that shader compilers do not emit, such as malformed input.
+//! Everything else is tested against the compiled shaders in `glsl.rs` and `hlsl.rs`.
+#![allow(dead_code)]
+
+use rspirv::dr::{Builder, Operand};
+use rspirv::spirv;
+
+/// Returns a builder for a SPIR-V 1.3 module with the `Shader` capability and `GLSL450` memory
+/// model
+pub fn builder() -> Builder {
+    let mut b = Builder::new();
+    b.set_version(1, 3);
+    b.capability(spirv::Capability::Shader);
+    b.memory_model(spirv::AddressingModel::Logical, spirv::MemoryModel::GLSL450);
+    b
+}
+
+/// Declares a `storage_class` variable at `binding` of set 0, through a pointer to `ty` in
+/// `pointer_storage_class`
+pub fn descriptor(
+    b: &mut Builder,
+    storage_class: spirv::StorageClass,
+    pointer_storage_class: spirv::StorageClass,
+    ty: u32,
+    binding: u32,
+) -> u32 {
+    let ptr = b.type_pointer(None, pointer_storage_class, ty);
+    let var = b.variable(ptr, None, storage_class, None);
+    b.decorate(
+        var,
+        spirv::Decoration::DescriptorSet,
+        [Operand::LiteralBit32(0)],
+    );
+    b.decorate(
+        var,
+        spirv::Decoration::Binding,
+        [Operand::LiteralBit32(binding)],
+    );
+    var
+}
+
+/// Declares a `Block` struct of `members`, each given as its type and `Offset`
+pub fn block(b: &mut Builder, members: &[(u32, u32)]) -> u32 {
+    let block = b.type_struct(members.iter().map(|&(ty, _)| ty).collect::<Vec<_>>());
+    b.decorate(block, spirv::Decoration::Block, []);
+    for (member, &(_, offset)) in members.iter().enumerate() {
+        b.member_decorate(
+            block,
+            member as u32,
+            spirv::Decoration::Offset,
+            [Operand::LiteralBit32(offset)],
+        );
+    }
+    block
+}
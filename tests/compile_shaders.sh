@@ -17,7 +17,31 @@ for hlsl in $current_dir/*cs.hlsl; do
     ${DXC} -E main -T cs_6_5 -spirv -fvk-use-scalar-layout $hlsl -Fo $spirv
 done
 
-for glsl in $current_dir/*.{comp,vert}; do
+for glsl in $current_dir/*.{comp,vert,geom,frag}; do
     spirv=${glsl%.*}-glsl.spv
     ${GLSLANG} -V $glsl -o $spirv
 done
+
+# Laid out with HLSL cbuffer packing rules, which has no GLSL layout qualifier
+${GLSLANG} -V -S frag --hlsl-offsets $current_dir/packing_hlsl_offsets.glsl -o $current_dir/packing_hlsl_offsets-glsl.spv
+
+# Two compute entry points linked into one module, once with and once without the global
+# variables that SPIR-V 1.4 lists in the entry point interface
+SPIRV_LINK=${SPIRV_LINK:-spirv-link}
+entry_points_dir=$(mktemp -d)
+for version in 1.3 1.4; do
+    for name in a b; do
+        ${GLSLANG} -V -S comp --target-env spirv$version -e main_$name --source-entrypoint main \
+            $current_dir/entry_point_$name.glsl -o $entry_points_dir/${name}_$version.spv
+    done
+    ${SPIRV_LINK} --target-env spv$version -o $current_dir/entry_points_spirv${version/./_}-glsl.spv \
+        $entry_points_dir/a_$version.spv $entry_points_dir/b_$version.spv
+done
+rm -r $entry_points_dir
+
+# Expected output of `Reflection::to_json()`
+SPIRV_CROSS=${SPIRV_CROSS:-spirv-cross}
+for spirv in $current_dir/{shader,workgroup_size,entry_points_spirv1_4,push_constants,layouts}-glsl.spv \
+    $current_dir/shader_cs-hlsl.spv; do
+    ${SPIRV_CROSS} $spirv --reflect --output ${spirv%.spv}.json
+done
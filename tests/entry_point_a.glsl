@@ -0,0 +1,27 @@
+#version 450
+
+// Compiled as `main_a` and linked with `entry_point_b.glsl` into a single module
+
+layout(local_size_x = 8, local_size_y = 8) in;
+
+layout(push_constant) uniform PushConstantsA
+{
+    uint value;
+}
+g_pushConstantsA;
+
+layout(set = 0, binding = 0) buffer BufferA
+{
+    uint data[];
+}
+g_bufferA;
+
+void helper()
+{
+    g_bufferA.data[0] = g_pushConstantsA.value;
+}
+
+void main()
+{
+    helper();
+}
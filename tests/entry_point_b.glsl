@@ -0,0 +1,28 @@
+#version 450
+
+// Compiled as `main_b` and linked with `entry_point_a.glsl` into a single module
+
+layout(local_size_x = 64) in;
+
+layout(push_constant) uniform PushConstantsB
+{
+    uint value;
+    uint unused[2];
+}
+g_pushConstantsB;
+
+layout(set = 0, binding = 1) buffer BufferB
+{
+    uint data[];
+}
+g_bufferB;
+
+void helper()
+{
+    g_bufferB.data[0] = g_pushConstantsB.value;
+}
+
+void main()
+{
+    helper();
+}
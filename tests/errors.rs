@@ -0,0 +1,273 @@
+mod common;
+
+use rspirv::dr::{InsertPoint, Instruction, Operand};
+use rspirv_reflect::*;
+
+#[test]
+fn truncated_instruction() {
+    let mut spirv = include_bytes!("shader-glsl.spv").to_vec();
+    // Claim the maximum word count for the first instruction after the 5-word header
+    spirv[5 * 4 + 2..5 * 4 + 4].copy_from_slice(&u16::MAX.to_le_bytes());
+    assert!(matches!(
+        Reflection::new_from_spirv(&spirv),
+        Err(ReflectError::TruncatedInstruction(5))
+    ));
+}
+
+#[test]
+fn invalid_spec_constant_op() {
+    let spirv = include_bytes!("layouts-glsl.spv");
+    let mut words = spirv
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        .collect::<Vec<_>>();
+    // Walk the instructions after the 5-word header to the `OpSpecConstantOp` sizing an array
+    let mut offset = 5;
+    while words[offset] & 0xffff != spirv::Op::SpecConstantOp as u32 {
+        offset += (words[offset] >> 16) as usize;
+    }
+    // `OpConstant` has operands the parser cannot decode without knowing the result type
+    words[offset + 3] = spirv::Op::Constant as u32;
+    let spirv = words
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect::<Vec<_>>();
+    assert!(matches!(
+        Reflection::new_from_spirv(&spirv),
+        Err(ReflectError::InvalidSpecConstantOp(o, 43)) if o == offset
+    ));
+}
+
+#[test]
+fn not_spirv() {
+    // Arbitrary data must not be mistaken for a truncated instruction
+    let text = include_bytes!("shader.comp");
+    assert!(matches!(
+        Reflection::new_from_spirv(text),
+        Err(ReflectError::ParseError(
+            rspirv::binary::ParseState::HeaderIncorrect
+        ))
+    ));
+
+    let mut spirv = include_bytes!("shader-glsl.spv").to_vec();
+    for word in spirv.chunks_exact_mut(4) {
+        word.reverse();
+    }
+    assert!(matches!(
+        Reflection::new_from_spirv(&spirv),
+        Err(ReflectError::ParseError(
+            rspirv::binary::ParseState::EndiannessUnsupported
+        ))
+    ));
+}
+
+#[test]
+fn duplicate_binding() {
+    let mut b = common::builder();
+    for _ in 0..2 {
+        let sampler = b.type_sampler();
+        common::descriptor(
+            &mut b,
+            spirv::StorageClass::UniformConstant,
+            spirv::StorageClass::UniformConstant,
+            sampler,
+            3,
+        );
+    }
+    let reflect = Reflection::new(b.module());
+    assert!(matches!(
+        reflect.get_descriptor_sets(),
+        Err(ReflectError::DuplicateBinding(0, 3))
+    ));
+}
+
+#[test]
+fn storage_class_mismatch() {
+    let mut b = common::builder();
+    let sampler = b.type_sampler();
+    common::descriptor(
+        &mut b,
+        spirv::StorageClass::Uniform,
+        spirv::StorageClass::UniformConstant,
+        sampler,
+        0,
+    );
+    let reflect = Reflection::new(b.module());
+    assert!(matches!(
+        reflect.get_descriptor_sets(),
+        Err(ReflectError::StorageClassMismatch(
+            _,
+            spirv::StorageClass::Uniform
+        ))
+    ));
+}
+
+#[test]
+fn cyclic_type() {
+    // An array of itself would otherwise recurse indefinitely
+    let mut b = common::builder();
+    let uint = b.type_int(32, 0);
+    let length = b.constant_bit32(uint, 2);
+    let array = b.id();
+    b.type_array_id(Some(array), array, length);
+    common::descriptor(
+        &mut b,
+        spirv::StorageClass::UniformConstant,
+        spirv::StorageClass::UniformConstant,
+        array,
+        0,
+    );
+    let reflect = Reflection::new(b.module());
+    assert!(matches!(
+        reflect.get_descriptor_sets(),
+        Err(ReflectError::ForwardTypeReference(_, _))
+    ));
+    assert!(matches!(
+        reflect.get_types(),
+        Err(ReflectError::ForwardTypeReference(_, _))
+    ));
+}
+
+#[test]
+fn unevaluated_array_length() {
+    // The length of the array is divided by zero, and has no default value
+    let mut b = common::builder();
+    let uint = b.type_int(32, 0);
+    let count = b.spec_constant_bit32(uint, 4);
+    b.decorate(count, spirv::Decoration::SpecId, [Operand::LiteralBit32(0)]);
+    let zero = b.constant_bit32(uint, 0);
+    let length = b.id();
+    b.insert_types_global_values(
+        InsertPoint::End,
+        Instruction::new(
+            spirv::Op::SpecConstantOp,
+            Some(uint),
+            Some(length),
+            vec![
+                Operand::LiteralSpecConstantOpInteger(spirv::Op::UDiv),
+                Operand::IdRef(count),
+                Operand::IdRef(zero),
+            ],
+        ),
+    );
+    let float = b.type_float(32);
+    let array = b.type_array(float, length);
+    b.decorate(
+        array,
+        spirv::Decoration::ArrayStride,
+        [Operand::LiteralBit32(4)],
+    );
+    let block = common::block(&mut b, &[(array, 0)]);
+    common::descriptor(
+        &mut b,
+        spirv::StorageClass::Uniform,
+        spirv::StorageClass::Uniform,
+        block,
+        0,
+    );
+    let reflect = Reflection::new(b.module());
+    assert!(matches!(
+        reflect.get_descriptor_sets(),
+        Err(ReflectError::UnevaluatedArrayLength(_))
+    ));
+    assert_eq!(
+        reflect.get_types().unwrap()[&array],
+        ReflectType::Array {
+            element: Box::new(ReflectType::Scalar(ScalarType::Float { width: 32 })),
+            length: ArrayLength::SpecConstantOp {
+                id: length,
+                default: None,
+            },
+            stride: Some(4),
+        }
+    );
+}
+
+#[test]
+fn too_many_vertex_input_locations() {
+    // One attribute per array element would need billions of them
+    let mut b = common::builder();
+    let void = b.type_void();
+    let void_fn = b.type_function(void, vec![]);
+    let float = b.type_float(32);
+    let vec4 = b.type_vector(float, 4);
+    let uint = b.type_int(32, 0);
+    let length = b.constant_bit32(uint, 0x7fff_ffff);
+    let array = b.type_array(vec4, length);
+    let ptr = b.type_pointer(None, spirv::StorageClass::Input, array);
+    let attrs = b.variable(ptr, None, spirv::StorageClass::Input, None);
+    b.decorate(
+        attrs,
+        spirv::Decoration::Location,
+        [Operand::LiteralBit32(0)],
+    );
+    b.name(attrs, "attrs");
+    let main = b
+        .begin_function(void, None, spirv::FunctionControl::NONE, void_fn)
+        .unwrap();
+    b.begin_block(None).unwrap();
+    b.ret().unwrap();
+    b.end_function().unwrap();
+    b.entry_point(spirv::ExecutionModel::Vertex, main, "main", [attrs]);
+
+    let reflect = Reflection::new(b.module());
+    let entry_points = reflect.get_entry_points().unwrap();
+    assert!(matches!(
+        reflect.get_vertex_input_attributes(&entry_points[0]),
+        Err(ReflectError::TooManyVertexInputLocations(name, 4096)) if name == "attrs"
+    ));
+}
+
+#[test]
+fn misaligned_member() {
+    // A `float` at an offset that is not a multiple of its alignment has no `#[repr(C)]` equivalent
+    let mut b = common::builder();
+    let float = b.type_float(32);
+    let block = common::block(&mut b, &[(float, 0), (float, 6)]);
+    common::descriptor(
+        &mut b,
+        spirv::StorageClass::Uniform,
+        spirv::StorageClass::Uniform,
+        block,
+        0,
+    );
+    let sets = Reflection::new(b.module())
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    let layout = sets[&0][&0].block.as_ref().unwrap();
+
+    assert!(matches!(
+        layout.to_rust("Block"),
+        Err(ReflectError::UnrepresentableLayout(_, _))
+    ));
+}
+
+#[test]
+fn remap_malformed_decoration() {
+    // A second `Binding` decoration without a literal, after the decorations of another descriptor
+    let mut b = common::builder();
+    let float = b.type_float(32);
+    let block = common::block(&mut b, &[(float, 0)]);
+    common::descriptor(
+        &mut b,
+        spirv::StorageClass::Uniform,
+        spirv::StorageClass::Uniform,
+        block,
+        0,
+    );
+    let var = common::descriptor(
+        &mut b,
+        spirv::StorageClass::Uniform,
+        spirv::StorageClass::Uniform,
+        block,
+        1,
+    );
+    b.decorate(var, spirv::Decoration::Binding, []);
+    let mut reflect = Reflection::new(b.module());
+    let original = reflect.assemble();
+
+    assert!(reflect
+        .remap_bindings(|set, binding, _| (set + 1, binding))
+        .is_err());
+    assert_eq!(reflect.assemble(), original);
+}
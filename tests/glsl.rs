@@ -1,4 +1,57 @@
 use rspirv_reflect::*;
+use std::sync::Arc;
+
+mod generated {
+    include!("codegen/large_arrays.rs");
+}
+
+/// An `rgba32f` storage image
+fn storage_image(dim: spirv::Dim) -> Option<ImageType> {
+    Some(ImageType {
+        sampled_type: ScalarType::Float { width: 32 },
+        dim,
+        depth: Some(false),
+        arrayed: false,
+        multisampled: false,
+        sampled: Some(false),
+        format: spirv::ImageFormat::Rgba32f,
+    })
+}
+
+/// A float image used with a sampler
+fn sampled_image(dim: spirv::Dim) -> Option<ImageType> {
+    Some(ImageType {
+        sampled_type: ScalarType::Float { width: 32 },
+        dim,
+        depth: Some(false),
+        arrayed: false,
+        multisampled: false,
+        sampled: Some(true),
+        format: spirv::ImageFormat::Unknown,
+    })
+}
+
+/// Layout of a block with a single, runtime-sized `float` array
+fn runtime_float_array_block(name: &str, member: &str) -> StructLayout {
+    StructLayout {
+        name: name.to_string(),
+        size: 0,
+        members: vec![MemberLayout {
+            name: member.to_string(),
+            offset: 0,
+            size: 0,
+            ty: TypeLayout::Array {
+                element: Box::new(TypeLayout::Numeric(NumericType {
+                    scalar: ScalarType::Float { width: 32 },
+                    vector_size: 1,
+                    column_count: 1,
+                })),
+                length: None,
+                stride: 4,
+            },
+        }],
+    }
+}
 
 #[test]
 fn bindings() {
@@ -27,7 +80,46 @@ fn bindings() {
         DescriptorInfo {
             name: "uniformBlock".to_string(),
             ty: DescriptorType::UNIFORM_BUFFER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: Some(StructLayout {
+                name: "UniformBlock".to_string(),
+                size: 20,
+                members: vec![
+                    MemberLayout {
+                        name: "g_input".to_string(),
+                        offset: 0,
+                        size: 16,
+                        ty: TypeLayout::Array {
+                            element: Box::new(TypeLayout::Numeric(NumericType {
+                                scalar: ScalarType::Float { width: 32 },
+                                vector_size: 1,
+                                column_count: 1
+                            })),
+                            length: Some(ArrayLength::Constant(1)),
+                            stride: 16
+                        }
+                    },
+                    MemberLayout {
+                        name: "nonuniform_index".to_string(),
+                        offset: 16,
+                        size: 4,
+                        ty: TypeLayout::Numeric(NumericType {
+                            scalar: ScalarType::Int {
+                                width: 32,
+                                signed: true
+                            },
+                            vector_size: 1,
+                            column_count: 1
+                        })
+                    }
+                ]
+            }),
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -36,7 +128,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_rimage2d".to_string(),
             ty: DescriptorType::STORAGE_IMAGE,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: false,
+            block: None,
+            image: storage_image(spirv::Dim::Dim2D),
+            access: AccessFlags::READ,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
 
@@ -45,7 +144,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_wimage2d".to_string(),
             ty: DescriptorType::STORAGE_IMAGE,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: false,
+            block: None,
+            image: storage_image(spirv::Dim::Dim2D),
+            access: AccessFlags::WRITE,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
 
@@ -54,7 +160,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_rwimage2d".to_string(),
             ty: DescriptorType::STORAGE_IMAGE,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: false,
+            block: None,
+            image: storage_image(spirv::Dim::Dim2D),
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
 
@@ -63,7 +176,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_texture2d".to_string(),
             ty: DescriptorType::SAMPLED_IMAGE,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: false,
+            block: None,
+            image: sampled_image(spirv::Dim::Dim2D),
+            access: AccessFlags::READ,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
 
@@ -72,7 +192,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_multiple_rwimage2d".to_string(),
             ty: DescriptorType::STORAGE_IMAGE,
-            binding_count: BindingCount::StaticSized(10)
+            binding_count: BindingCount::StaticSized(10),
+            is_statically_used: false,
+            block: None,
+            image: storage_image(spirv::Dim::Dim2D),
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
 
@@ -81,7 +208,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_multiple_texture2d".to_string(),
             ty: DescriptorType::SAMPLED_IMAGE,
-            binding_count: BindingCount::StaticSized(10)
+            binding_count: BindingCount::StaticSized(10),
+            is_statically_used: false,
+            block: None,
+            image: sampled_image(spirv::Dim::Dim2D),
+            access: AccessFlags::READ,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
 
@@ -90,7 +224,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_bindless_rwimage2d".to_string(),
             ty: DescriptorType::STORAGE_IMAGE,
-            binding_count: BindingCount::StaticSized(6)
+            binding_count: BindingCount::StaticSized(6),
+            is_statically_used: true,
+            block: None,
+            image: storage_image(spirv::Dim::Dim2D),
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -99,7 +240,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_bindless_texture2d".to_string(),
             ty: DescriptorType::SAMPLED_IMAGE,
-            binding_count: BindingCount::StaticSized(1)
+            binding_count: BindingCount::StaticSized(1),
+            is_statically_used: false,
+            block: None,
+            image: sampled_image(spirv::Dim::Dim2D),
+            access: AccessFlags::READ,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
 
@@ -108,7 +256,27 @@ fn bindings() {
         DescriptorInfo {
             name: "g_bindless_buffer".to_string(),
             ty: DescriptorType::UNIFORM_BUFFER,
-            binding_count: BindingCount::StaticSized(11)
+            binding_count: BindingCount::StaticSized(11),
+            is_statically_used: true,
+            block: Some(StructLayout {
+                name: "BindlessBufferBlockName".to_string(),
+                size: 4,
+                members: vec![MemberLayout {
+                    name: "x".to_string(),
+                    offset: 0,
+                    size: 4,
+                    ty: TypeLayout::Numeric(NumericType {
+                        scalar: ScalarType::Float { width: 32 },
+                        vector_size: 1,
+                        column_count: 1
+                    })
+                }]
+            }),
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
     assert_eq!(
@@ -116,7 +284,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_samplerimage2d".to_string(),
             ty: DescriptorType::COMBINED_IMAGE_SAMPLER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: false,
+            block: None,
+            image: sampled_image(spirv::Dim::Dim2D),
+            access: AccessFlags::READ,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
 
@@ -125,7 +300,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_imagebuffer".to_string(),
             ty: DescriptorType::STORAGE_TEXEL_BUFFER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: false,
+            block: None,
+            image: storage_image(spirv::Dim::DimBuffer),
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
     assert_eq!(
@@ -133,7 +315,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_samplerbuffer".to_string(),
             ty: DescriptorType::UNIFORM_TEXEL_BUFFER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: false,
+            block: None,
+            image: sampled_image(spirv::Dim::DimBuffer),
+            access: AccessFlags::READ,
+            used_access: AccessFlags::NONE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::NONE
         }
     );
 
@@ -142,7 +331,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_storageBuffer".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::Unbounded
+            binding_count: BindingCount::Unbounded,
+            is_statically_used: true,
+            block: Some(runtime_float_array_block("NamedStorageBuffer", "data")),
+            image: None,
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -151,7 +347,17 @@ fn bindings() {
         DescriptorInfo {
             name: "bufferBlock".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: Some(runtime_float_array_block(
+                "BufferBlock",
+                "g_unnamedStorageBuffer"
+            )),
+            image: None,
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 }
@@ -176,7 +382,1184 @@ fn push_constants() {
         range,
         PushConstantInfo {
             offset: 0,
-            size: 16
+            size: 16,
+            stages: ShaderStageFlags::VERTEX
+        }
+    );
+
+    let layout = reflect
+        .get_push_constant_layout()
+        .expect("failed to extract push constant layout")
+        .expect("defined push constants not detected");
+
+    assert_eq!(
+        layout,
+        StructLayout {
+            name: "Registers".to_string(),
+            size: 16,
+            members: vec![
+                MemberLayout {
+                    name: "mesh_buffer".to_string(),
+                    offset: 0,
+                    size: 8,
+                    ty: TypeLayout::PhysicalPointer,
+                },
+                MemberLayout {
+                    name: "index_buffer".to_string(),
+                    offset: 8,
+                    size: 8,
+                    ty: TypeLayout::PhysicalPointer,
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn interface_variables() {
+    let spirv = include_bytes!("push_constants-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let entry_points = reflect
+        .get_entry_points()
+        .expect("Failed to extract entry points");
+
+    let variables = reflect
+        .get_interface_variables(&entry_points[0])
+        .expect("Failed to extract interface variables");
+
+    dbg!(&variables);
+
+    let float = ScalarType::Float { width: 32 };
+    let vec = |vector_size| NumericType {
+        scalar: float,
+        vector_size,
+        column_count: 1,
+    };
+
+    assert_eq!(
+        variables,
+        vec![
+            InterfaceVariable {
+                name: "gl_VertexIndex".to_string(),
+                storage_class: spirv::StorageClass::Input,
+                location: None,
+                component: 0,
+                builtin: Some(spirv::BuiltIn::VertexIndex),
+                ty: NumericType {
+                    scalar: ScalarType::Int {
+                        width: 32,
+                        signed: true
+                    },
+                    vector_size: 1,
+                    column_count: 1
+                },
+                array_dims: vec![],
+                interpolation: InterpolationFlags::NONE,
+                patch: false,
+                per_primitive: false
+            },
+            InterfaceVariable {
+                name: "gl_Position".to_string(),
+                storage_class: spirv::StorageClass::Output,
+                location: None,
+                component: 0,
+                builtin: Some(spirv::BuiltIn::Position),
+                ty: vec(4),
+                array_dims: vec![],
+                interpolation: InterpolationFlags::NONE,
+                patch: false,
+                per_primitive: false
+            },
+            InterfaceVariable {
+                name: "gl_PointSize".to_string(),
+                storage_class: spirv::StorageClass::Output,
+                location: None,
+                component: 0,
+                builtin: Some(spirv::BuiltIn::PointSize),
+                ty: vec(1),
+                array_dims: vec![],
+                interpolation: InterpolationFlags::NONE,
+                patch: false,
+                per_primitive: false
+            },
+            InterfaceVariable {
+                name: "gl_ClipDistance".to_string(),
+                storage_class: spirv::StorageClass::Output,
+                location: None,
+                component: 0,
+                builtin: Some(spirv::BuiltIn::ClipDistance),
+                ty: vec(1),
+                array_dims: vec![1],
+                interpolation: InterpolationFlags::NONE,
+                patch: false,
+                per_primitive: false
+            },
+            InterfaceVariable {
+                name: "gl_CullDistance".to_string(),
+                storage_class: spirv::StorageClass::Output,
+                location: None,
+                component: 0,
+                builtin: Some(spirv::BuiltIn::CullDistance),
+                ty: vec(1),
+                array_dims: vec![1],
+                interpolation: InterpolationFlags::NONE,
+                patch: false,
+                per_primitive: false
+            },
+            InterfaceVariable {
+                name: "uv".to_string(),
+                storage_class: spirv::StorageClass::Output,
+                location: Some(0),
+                component: 0,
+                builtin: None,
+                ty: vec(2),
+                array_dims: vec![],
+                interpolation: InterpolationFlags::NONE,
+                patch: false,
+                per_primitive: false
+            },
+        ]
+    );
+}
+
+#[test]
+fn nested_interface_block() {
+    let spirv = include_bytes!("nested_block-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let entry_points = reflect
+        .get_entry_points()
+        .expect("Failed to extract entry points");
+
+    let variables = reflect
+        .get_interface_variables(&entry_points[0])
+        .expect("Failed to extract interface variables");
+
+    let summary = variables
+        .iter()
+        .filter(|variable| !variable.is_builtin())
+        .map(|variable| {
+            (
+                variable.name.as_str(),
+                variable.location,
+                variable.ty.vector_size,
+                variable.interpolation,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let flat = InterpolationFlags::FLAT;
+    assert_eq!(
+        summary,
+        vec![
+            ("vs_out.position", Some(1), 4, InterpolationFlags::NONE),
+            ("vs_out.material.uv", Some(2), 2, flat),
+            ("vs_out.material.lights[0].direction", Some(3), 3, flat),
+            ("vs_out.material.lights[0].color", Some(4), 4, flat),
+            ("vs_out.material.lights[1].direction", Some(5), 3, flat),
+            ("vs_out.material.lights[1].color", Some(6), 4, flat),
+            ("vs_out.depth", Some(7), 1, InterpolationFlags::NONE),
+        ]
+    );
+}
+
+#[test]
+fn runtime_array_stride() {
+    let spirv = include_bytes!("shader-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    let block = |set: u32, binding: u32| sets[&set][&binding].block.as_ref().unwrap();
+
+    assert_eq!(block(0, 0).runtime_array_stride(), None);
+    assert_eq!(block(4, 2).runtime_array_stride(), None);
+    assert_eq!(block(6, 2).runtime_array_stride(), Some(4));
+    assert_eq!(block(6, 3).runtime_array_stride(), Some(4));
+}
+
+#[test]
+fn specialized_array_layouts() {
+    let spirv = include_bytes!("layouts-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    let block = sets[&0][&0].block.as_ref().unwrap();
+    assert_eq!(block.name, "SpecConstantArrays");
+    assert_eq!(block.size, 192);
+
+    let fixed_count = &block.members[0];
+    assert_eq!(fixed_count.name, "fixed_count");
+    assert_eq!(fixed_count.size, 64);
+    assert!(matches!(
+        fixed_count.ty,
+        TypeLayout::Array {
+            length: Some(ArrayLength::SpecConstant {
+                spec_id: 0,
+                default: 4,
+            }),
+            stride: 16,
+            ..
+        }
+    ));
+
+    // `COUNT * 2` is an `OpSpecConstantOp`, evaluated with the default value of `COUNT`
+    let computed_count = &block.members[1];
+    assert_eq!(computed_count.name, "computed_count");
+    assert_eq!(computed_count.offset, 64);
+    assert_eq!(computed_count.size, 128);
+    assert!(matches!(
+        computed_count.ty,
+        TypeLayout::Array {
+            length: Some(ArrayLength::SpecConstantOp {
+                default: Some(8),
+                ..
+            }),
+            stride: 16,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn deeply_nested_layout() {
+    let spirv = include_bytes!("layouts-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    // Every level nests the previous struct twice, which must not be laid out (or stored) once
+    // per occurrence
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    let block = sets[&0][&1].block.as_ref().unwrap();
+    assert_eq!(block.name, "DeeplyNested");
+    // std140 aligns each struct to 16 bytes, except for the padding after its last member
+    assert_eq!(block.size, (16 << 22) - 12);
+
+    let mut ty = &block.members[0].ty;
+    for level in (1..=22).rev() {
+        let nested = match ty {
+            TypeLayout::Struct(nested) => nested,
+            ty => panic!("Unexpected {:?}", ty),
+        };
+        assert_eq!(nested.name, format!("Nested{}", level));
+        assert_eq!(nested.size, (16 << level) - 12);
+        assert_eq!(nested.members[1].offset, 16 << (level - 1));
+        ty = &nested.members[0].ty;
+    }
+}
+
+#[test]
+fn deeply_nested_types() {
+    let spirv = include_bytes!("layouts-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let types = reflect.get_types().expect("Failed to resolve types");
+    let nested = types
+        .values()
+        .find_map(|ty| match ty {
+            ReflectType::Struct(s) if s.name == "DeeplyNested" => Some(s),
+            _ => None,
+        })
+        .unwrap();
+
+    let mut ty = &nested.members[0].ty;
+    for level in (1..=22).rev() {
+        let nested = match ty {
+            ReflectType::Struct(nested) => nested,
+            ty => panic!("Unexpected {:?}", ty),
+        };
+        assert_eq!(nested.name, format!("Nested{}", level));
+        // Both members refer to the same resolved struct
+        match (&nested.members[0].ty, &nested.members[1].ty) {
+            (ReflectType::Struct(a), ReflectType::Struct(b)) => assert!(Arc::ptr_eq(a, b)),
+            tys => panic!("Unexpected {:?}", tys),
+        }
+        ty = &nested.members[0].ty;
+    }
+}
+
+#[test]
+fn deeply_nested_layout_rules() {
+    let spirv = include_bytes!("layouts-glsl.spv");
+
+    let sets = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code")
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    let block = sets[&0][&1].block.as_ref().unwrap();
+
+    // Every struct is validated once, rather than once for each of its 2^22 occurrences
+    assert_eq!(
+        block.layout_rules(),
+        [LayoutRule::Std140, LayoutRule::HlslCbuffer]
+    );
+    let violations = block.validate(LayoutRule::Std430);
+    assert_eq!(violations.len(), 22);
+    assert_eq!(
+        violations[0],
+        LayoutViolation {
+            member: format!("nested{}.b", ".a".repeat(21)),
+            decoration: spirv::Decoration::Offset,
+            actual: 16,
+            expected: 4,
+        }
+    );
+    assert_eq!(violations[21].member, "nested.b");
+}
+
+#[test]
+fn deeply_nested_to_rust() {
+    let spirv = include_bytes!("layouts-glsl.spv");
+
+    let sets = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code")
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    let block = sets[&0][&1].block.as_ref().unwrap();
+
+    // Every nested struct is declared once, and reused by both of its occurrences
+    let source = block.to_rust("DeeplyNested").unwrap();
+    assert_eq!(source.matches("pub struct ").count(), 24);
+    assert!(source.contains(
+        "pub struct Nested22 {
+    pub a: Nested21,
+    pub _padding0: [u8; 12],
+    pub b: Nested21,
+}"
+    ));
+}
+
+#[test]
+fn vertex_input_attributes() {
+    let spirv = include_bytes!("vertex_input-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let entry_points = reflect
+        .get_entry_points()
+        .expect("Failed to extract entry points");
+    let attributes = reflect
+        .get_vertex_input_attributes(&entry_points[0])
+        .expect("Failed to extract vertex input attributes");
+
+    dbg!(&attributes);
+
+    let attribute = |location, format, size, name: &str| VertexInputAttribute {
+        location,
+        format,
+        size,
+        name: name.to_string(),
+    };
+
+    assert_eq!(
+        attributes,
+        vec![
+            attribute(0, Format::R32G32B32_SFLOAT, 12, "in_position"),
+            attribute(1, Format::R32G32_SFLOAT, 8, "in_uv"),
+            attribute(2, Format::R32G32B32A32_UINT, 16, "in_color"),
+            attribute(3, Format::R32G32B32A32_SFLOAT, 16, "in_transform"),
+            attribute(4, Format::R32G32B32A32_SFLOAT, 16, "in_transform"),
+            attribute(5, Format::R32G32B32A32_SFLOAT, 16, "in_transform"),
+            attribute(6, Format::R32G32B32A32_SFLOAT, 16, "in_transform"),
+            attribute(7, Format::R64G64B64A64_SFLOAT, 32, "in_weights"),
+        ]
+    );
+
+    let layout = VertexInputLayout::packed(&attributes);
+    assert_eq!(layout.stride, 132);
+    assert_eq!(
+        layout
+            .attributes
+            .iter()
+            .map(|a| (a.location, a.offset))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, 0),
+            (1, 12),
+            (2, 20),
+            (3, 36),
+            (4, 52),
+            (5, 68),
+            (6, 84),
+            (7, 100)
+        ]
+    );
+}
+
+#[test]
+fn specialization_constants() {
+    let spirv = include_bytes!("specialization-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let constants = reflect
+        .get_specialization_constants()
+        .expect("Failed to extract specialization constants");
+
+    dbg!(&constants);
+
+    // `INTERNAL` is computed by an `OpSpecConstantOp` and cannot be specialized by itself
+
+    let constant = |spec_id, name: &str, ty, default_value| SpecializationConstant {
+        spec_id,
+        name: name.to_string(),
+        ty,
+        default_value,
+    };
+
+    assert_eq!(
+        constants,
+        vec![
+            constant(
+                0,
+                "OFFSET",
+                ScalarType::Int {
+                    width: 32,
+                    signed: true
+                },
+                SpecializationConstantValue::Int(-5)
+            ),
+            constant(
+                1,
+                "SEED",
+                ScalarType::Int {
+                    width: 64,
+                    signed: false
+                },
+                SpecializationConstantValue::UInt(1 << 40)
+            ),
+            constant(
+                2,
+                "SCALE",
+                ScalarType::Float { width: 32 },
+                SpecializationConstantValue::Float(1.5)
+            ),
+            constant(
+                3,
+                "USE_SHADOWS",
+                ScalarType::Bool,
+                SpecializationConstantValue::Bool(true)
+            ),
+            constant(
+                4,
+                "USE_FOG",
+                ScalarType::Bool,
+                SpecializationConstantValue::Bool(false)
+            ),
+            constant(
+                10,
+                "BIAS",
+                ScalarType::Float { width: 64 },
+                SpecializationConstantValue::Float(0.25)
+            ),
+        ]
+    );
+
+    assert_eq!(
+        constants.iter().map(|c| c.size()).collect::<Vec<_>>(),
+        vec![4, 8, 4, 4, 4, 8]
+    );
+}
+
+#[test]
+fn push_constant_offset() {
+    let spirv = include_bytes!("push_constant_offset-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let range = reflect
+        .get_push_constant_range()
+        .expect("failed to extract push constants")
+        .expect("defined push constants not detected");
+
+    assert_eq!(
+        range,
+        PushConstantInfo {
+            offset: 16,
+            size: 12,
+            stages: ShaderStageFlags::FRAGMENT
         }
-    )
+    );
+
+    let layout = reflect
+        .get_push_constant_layout()
+        .expect("failed to extract push constant layout")
+        .expect("defined push constants not detected");
+
+    assert_eq!(layout.name, "Registers");
+    assert_eq!(layout.size, 28);
+    assert_eq!(
+        layout
+            .members
+            .iter()
+            .map(|member| (member.name.as_str(), member.offset, member.size))
+            .collect::<Vec<_>>(),
+        vec![("bias", 16, 8), ("scale", 24, 4)]
+    );
+}
+
+#[test]
+fn spec_constant_array_types() {
+    let spirv = include_bytes!("layouts-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let types = reflect.get_types().expect("Failed to resolve types");
+
+    let (block_id, block) = types
+        .iter()
+        .find_map(|(&id, ty)| match ty {
+            ReflectType::Struct(s) if s.name == "SpecConstantArrays" => Some((id, s)),
+            _ => None,
+        })
+        .expect("SpecConstantArrays struct not found");
+
+    let element = Box::new(ReflectType::Vector {
+        scalar: ScalarType::Float { width: 32 },
+        size: 4,
+    });
+    assert_eq!(
+        block.members[0].ty,
+        ReflectType::Array {
+            element: element.clone(),
+            length: ArrayLength::SpecConstant {
+                spec_id: 0,
+                default: 4
+            },
+            stride: Some(16),
+        }
+    );
+    assert!(matches!(
+        block.members[1].ty,
+        ReflectType::Array {
+            length: ArrayLength::SpecConstantOp {
+                default: Some(8),
+                ..
+            },
+            stride: Some(16),
+            ..
+        }
+    ));
+
+    assert!(types.values().any(|ty| *ty
+        == ReflectType::Pointer {
+            storage_class: spirv::StorageClass::Uniform,
+            pointee: block_id,
+        }));
+}
+
+#[test]
+fn recursive_physical_pointer() {
+    let spirv = include_bytes!("push_constants-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let types = reflect.get_types().expect("Failed to resolve types");
+
+    let registers = types
+        .values()
+        .find_map(|ty| match ty {
+            ReflectType::Struct(s) if s.name == "Registers" => Some(s),
+            _ => None,
+        })
+        .expect("Registers struct not found");
+
+    let mesh_buffer = match registers.members[0].ty {
+        ReflectType::Pointer {
+            storage_class: spirv::StorageClass::PhysicalStorageBuffer,
+            pointee,
+        } => &types[&pointee],
+        ref ty => panic!("Unexpected type {:?}", ty),
+    };
+    assert!(matches!(mesh_buffer, ReflectType::Struct(s) if s.name == "MeshBuffer"));
+}
+
+#[test]
+fn used_access() {
+    let spirv = include_bytes!("access-glsl.spv");
+
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    let access = |binding: u32| {
+        let descriptor = &sets[&0][&binding];
+        (descriptor.access, descriptor.used_access)
+    };
+
+    assert_eq!(
+        access(0),
+        (
+            AccessFlags::READ_WRITE,
+            AccessFlags::READ_WRITE | AccessFlags::ATOMIC
+        )
+    );
+    assert_eq!(access(1), (AccessFlags::READ_WRITE, AccessFlags::WRITE));
+    assert_eq!(access(2), (AccessFlags::READ_WRITE, AccessFlags::WRITE));
+}
+
+#[test]
+fn merge_stages() {
+    let vertex = Reflection::new_from_spirv(include_bytes!("pipeline_vs-glsl.spv"))
+        .expect("Failed to create reflection module from spirv code");
+    let fragment = Reflection::new_from_spirv(include_bytes!("pipeline_fs-glsl.spv"))
+        .expect("Failed to create reflection module from spirv code");
+
+    let pipeline =
+        PipelineReflection::new(&[vertex, fragment]).expect("Failed to merge pipeline stages");
+
+    dbg!(&pipeline);
+
+    let set = &pipeline.descriptor_sets[&0];
+    assert_eq!(set.len(), 3);
+
+    assert_eq!(set[&0].ty, DescriptorType::UNIFORM_BUFFER);
+    assert_eq!(
+        set[&0].stages,
+        ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT
+    );
+
+    assert_eq!(set[&1].ty, DescriptorType::STORAGE_BUFFER);
+    assert_eq!(set[&1].stages, ShaderStageFlags::FRAGMENT);
+    assert!(set[&1].is_statically_used);
+    assert_eq!(set[&1].used_access, AccessFlags::READ);
+
+    assert_eq!(set[&2].stages, ShaderStageFlags::NONE);
+    assert!(!set[&2].is_statically_used);
+
+    assert_eq!(
+        pipeline.push_constant_ranges,
+        vec![PushConstantInfo {
+            stages: ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT,
+            offset: 0,
+            size: 4,
+        }]
+    );
+}
+
+#[test]
+fn conflicting_descriptor_type() {
+    let vertex = Reflection::new_from_spirv(include_bytes!("pipeline_vs-glsl.spv"))
+        .expect("Failed to create reflection module from spirv code");
+    let fragment = Reflection::new_from_spirv(include_bytes!("pipeline_conflict_fs-glsl.spv"))
+        .expect("Failed to create reflection module from spirv code");
+
+    assert!(matches!(
+        PipelineReflection::new(&[vertex, fragment]),
+        Err(ReflectError::ConflictingDescriptorType(
+            0,
+            0,
+            DescriptorType::UNIFORM_BUFFER,
+            DescriptorType::STORAGE_BUFFER
+        ))
+    ));
+}
+
+#[test]
+fn conflicting_bindings() {
+    let vertex = Reflection::new_from_spirv(include_bytes!("pipeline_vs-glsl.spv"))
+        .expect("Failed to create reflection module from spirv code");
+    let fragment = Reflection::new_from_spirv(include_bytes!("pipeline_conflicts_fs-glsl.spv"))
+        .expect("Failed to create reflection module from spirv code");
+
+    let conflicts = match PipelineReflection::new(&[vertex, fragment]) {
+        Err(ReflectError::ConflictingBindings(conflicts)) => conflicts,
+        result => panic!("Expected multiple conflicts, got {:?}", result),
+    };
+    assert_eq!(conflicts.len(), 2);
+    assert!(matches!(
+        conflicts[0],
+        ReflectError::ConflictingDescriptorType(
+            0,
+            0,
+            DescriptorType::UNIFORM_BUFFER,
+            DescriptorType::STORAGE_BUFFER
+        )
+    ));
+    assert!(matches!(
+        conflicts[1],
+        ReflectError::ConflictingBindingCount(
+            0,
+            1,
+            BindingCount::One,
+            BindingCount::StaticSized(2)
+        )
+    ));
+}
+
+#[test]
+fn comparison_sampling() {
+    let spirv = include_bytes!("sampling-glsl.spv");
+    let sets = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code")
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    assert_eq!(
+        sets[&0]
+            .values()
+            .map(|descriptor| descriptor.sampling)
+            .collect::<Vec<_>>(),
+        vec![
+            SamplingFlags::COMPARE,
+            SamplingFlags::COMPARE,
+            SamplingFlags::SAMPLE,
+            SamplingFlags::SAMPLE,
+            SamplingFlags::NONE,
+        ]
+    );
+}
+
+#[cfg(feature = "wgpu")]
+#[test]
+fn wgpu_comparison_sampler() {
+    use std::num::NonZeroU32;
+    use wgpu_types as wgt;
+
+    let spirv = include_bytes!("sampling-glsl.spv");
+    let sets = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code")
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    let entries = wgpu_bind_group_layout_entries(&sets[&0], NonZeroU32::new(1).unwrap()).unwrap();
+
+    let sample_type = |entry: &wgt::BindGroupLayoutEntry| match entry.ty {
+        wgt::BindingType::Texture { sample_type, .. } => sample_type,
+        ty => panic!("Expected a texture, got {:?}", ty),
+    };
+    assert_eq!(sample_type(&entries[0]), wgt::TextureSampleType::Depth);
+    assert_eq!(
+        sample_type(&entries[2]),
+        wgt::TextureSampleType::Float { filterable: true }
+    );
+    assert_eq!(
+        entries[1].ty,
+        wgt::BindingType::Sampler(wgt::SamplerBindingType::Comparison)
+    );
+    assert_eq!(
+        entries[3].ty,
+        wgt::BindingType::Sampler(wgt::SamplerBindingType::Filtering)
+    );
+}
+
+#[test]
+fn large_arrays() {
+    let spirv = include_bytes!("large_arrays-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    let layout = sets[&0][&0].block.as_ref().unwrap();
+
+    assert_eq!(
+        layout.to_rust("LargeArrays").unwrap(),
+        include_str!("codegen/large_arrays.rs")
+    );
+
+    // `[f32; 40]` and the 136 byte gap are only `Pod` with `min_const_generics`
+    let large_arrays = generated::LargeArrays {
+        count: 1,
+        ..bytemuck::Zeroable::zeroed()
+    };
+    let bytes = bytemuck::bytes_of(&large_arrays);
+    assert_eq!(bytes.len(), generated::LargeArrays::SIZE);
+    assert_eq!(bytes.len() as u32, layout.size);
+}
+
+#[test]
+fn nested_struct() {
+    let spirv = include_bytes!("codegen-glsl.spv");
+    let sets = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code")
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    let layout = sets[&0][&0].block.as_ref().unwrap();
+
+    assert_eq!(
+        layout.to_rust("Scene").unwrap(),
+        "\
+/// Layout of `Light`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Light {
+    pub position: [f32; 3],
+    pub r#type: f32,
+    pub intensity: f32,
+    pub _padding0: [u8; 12],
+}
+
+const _: () = assert!(::std::mem::size_of::<Light>() == 32);
+const _: () = assert!(::std::mem::offset_of!(Light, position) == 0);
+const _: () = assert!(::std::mem::offset_of!(Light, r#type) == 12);
+const _: () = assert!(::std::mem::offset_of!(Light, intensity) == 16);
+
+/// Layout of `Scene`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Scene {
+    pub view: [[f32; 4]; 3],
+    pub lights: [Light; 2],
+    pub exposure: f32,
+}
+
+impl Scene {
+    /// Size of the block in bytes, excluding the padding at the end of this struct
+    pub const SIZE: usize = 116;
+}
+
+const _: () = assert!(::std::mem::size_of::<Scene>() == 116);
+const _: () = assert!(::std::mem::offset_of!(Scene, view) == 0);
+const _: () = assert!(::std::mem::offset_of!(Scene, lights) == 48);
+const _: () = assert!(::std::mem::offset_of!(Scene, exposure) == 112);
+"
+    );
+}
+
+/// Returns the blocks of `packing.frag`, which holds the same members with each GLSL packing rule
+fn packing_blocks() -> [StructLayout; 3] {
+    let spirv = include_bytes!("packing-glsl.spv");
+    let mut sets = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code")
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    let mut set = sets.remove(&0).unwrap();
+    [0, 1, 2].map(|binding| set.remove(&binding).unwrap().block.unwrap())
+}
+
+#[test]
+fn layout_rules() {
+    let [std140, std430, scalar] = packing_blocks();
+    assert_eq!(std140.layout_rules(), [LayoutRule::Std140]);
+    assert_eq!(std430.layout_rules(), [LayoutRule::Std430]);
+    assert_eq!(scalar.layout_rules(), [LayoutRule::Scalar]);
+
+    // `b` fits in the register after `a`, and `c` is moved to the next one
+    let spirv = include_bytes!("packing_hlsl_offsets-glsl.spv");
+    let sets = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code")
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    let hlsl_offsets = sets[&0][&0].block.as_ref().unwrap();
+    assert_eq!(hlsl_offsets.layout_rules(), [LayoutRule::HlslCbuffer]);
+    assert_eq!(
+        hlsl_offsets.validate(LayoutRule::Scalar),
+        [LayoutViolation {
+            member: "c".to_owned(),
+            decoration: spirv::Decoration::Offset,
+            actual: 16,
+            expected: 12,
+        }]
+    );
+}
+
+#[test]
+fn layout_violations() {
+    let [std140, _, scalar] = packing_blocks();
+
+    // A std140 block validated as std430 only reports the strides, not the members placed
+    // after the larger array
+    assert_eq!(
+        std140.validate(LayoutRule::Std430),
+        [
+            LayoutViolation {
+                member: "a".to_owned(),
+                decoration: spirv::Decoration::ArrayStride,
+                actual: 16,
+                expected: 4,
+            },
+            LayoutViolation {
+                member: "d".to_owned(),
+                decoration: spirv::Decoration::MatrixStride,
+                actual: 16,
+                expected: 8,
+            },
+        ]
+    );
+
+    // `b` fits in the register after the unpadded last element of `a`
+    assert_eq!(
+        std140.validate(LayoutRule::HlslCbuffer),
+        [LayoutViolation {
+            member: "b".to_owned(),
+            decoration: spirv::Decoration::Offset,
+            actual: 32,
+            expected: 20,
+        }]
+    );
+
+    // `b` straddles two registers
+    assert_eq!(
+        scalar.validate(LayoutRule::HlslCbuffer)[1],
+        LayoutViolation {
+            member: "b".to_owned(),
+            decoration: spirv::Decoration::Offset,
+            actual: 8,
+            expected: 16,
+        }
+    );
+}
+
+/// Returns the reflection of a compiled shader and its only entry point
+fn single_entry_point(spirv: &[u8]) -> (Reflection, EntryPoint) {
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let entry_point = reflect
+        .get_entry_points()
+        .expect("Failed to extract entry points")
+        .remove(0);
+    (reflect, entry_point)
+}
+
+/// Summarizes each mismatch as its kind and the name of the variables involved
+fn summarize(mismatches: &[InterfaceMismatch]) -> Vec<(&'static str, &str)> {
+    mismatches
+        .iter()
+        .map(|mismatch| match mismatch {
+            InterfaceMismatch::MissingOutput { input } => ("missing", input.name.as_str()),
+            InterfaceMismatch::UnusedOutput { output } => ("unused", output.name.as_str()),
+            InterfaceMismatch::TypeMismatch { input, .. } => ("type", input.name.as_str()),
+            InterfaceMismatch::InterpolationMismatch { output, input } => {
+                assert_eq!(output.location, input.location);
+                ("interpolation", input.name.as_str())
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn vertex_to_fragment_interface() {
+    let (vertex, vertex_entry_point) = single_entry_point(include_bytes!("matching_vs-glsl.spv"));
+    let (fragment, fragment_entry_point) =
+        single_entry_point(include_bytes!("matching_fs-glsl.spv"));
+
+    let mismatches = vertex
+        .match_interface(&vertex_entry_point, &fragment, &fragment_entry_point)
+        .expect("Failed to match interfaces");
+    assert_eq!(
+        summarize(&mismatches),
+        [
+            ("interpolation", "color"),
+            ("type", "normal"),
+            ("missing", "tangent"),
+            ("unused", "extra"),
+        ]
+    );
+
+    assert_eq!(
+        mismatches
+            .iter()
+            .map(InterfaceMismatch::is_error)
+            .collect::<Vec<_>>(),
+        [false, true, true, false]
+    );
+
+    match &mismatches[0] {
+        InterfaceMismatch::InterpolationMismatch { output, input } => {
+            assert_eq!(output.interpolation, InterpolationFlags::FLAT);
+            assert_eq!(input.interpolation, InterpolationFlags::NONE);
+        }
+        mismatch => panic!("Unexpected {:?}", mismatch),
+    }
+
+    // Only the decorations of fragment shader inputs affect interpolation, so `flat` on just
+    // the input is not reported
+    let (fragment, fragment_entry_point) =
+        single_entry_point(include_bytes!("matching_flat_fs-glsl.spv"));
+    let mismatches = vertex
+        .match_interface(&vertex_entry_point, &fragment, &fragment_entry_point)
+        .expect("Failed to match interfaces");
+    assert_eq!(
+        summarize(&mismatches),
+        [("unused", "normal"), ("unused", "extra")]
+    );
+}
+
+#[test]
+fn vertex_to_geometry_interface() {
+    let (vertex, vertex_entry_point) =
+        single_entry_point(include_bytes!("matching_arrays_vs-glsl.spv"));
+    let (geometry, geometry_entry_point) =
+        single_entry_point(include_bytes!("matching_gs-glsl.spv"));
+
+    // The per-vertex array of geometry shader inputs is not compared
+    let mismatches = vertex
+        .match_interface(&vertex_entry_point, &geometry, &geometry_entry_point)
+        .expect("Failed to match interfaces");
+    assert_eq!(summarize(&mismatches), [("type", "weights")]);
+
+    match &mismatches[0] {
+        InterfaceMismatch::TypeMismatch { output, input } => {
+            assert_eq!(output.array_dims, [3]);
+            assert_eq!(input.array_dims, [2]);
+        }
+        mismatch => panic!("Unexpected {:?}", mismatch),
+    }
+}
+
+#[test]
+fn overlapping_interface_locations() {
+    let (vertex, vertex_entry_point) =
+        single_entry_point(include_bytes!("matching_arrays_vs-glsl.spv"));
+    let (fragment, fragment_entry_point) =
+        single_entry_point(include_bytes!("matching_arrays_fs-glsl.spv"));
+
+    // `weights` occupies locations 0 to 2, so it is neither missing nor unused
+    let mismatches = vertex
+        .match_interface(&vertex_entry_point, &fragment, &fragment_entry_point)
+        .expect("Failed to match interfaces");
+    assert_eq!(summarize(&mismatches), [("type", "weight")]);
+
+    match &mismatches[0] {
+        InterfaceMismatch::TypeMismatch { output, input } => {
+            assert_eq!(output.name, "weights");
+            assert_eq!(output.location, Some(0));
+            assert_eq!(input.location, Some(1));
+        }
+        mismatch => panic!("Unexpected {:?}", mismatch),
+    }
+}
+
+#[test]
+fn entry_points() {
+    let spirv = include_bytes!("entry_points_spirv1_4-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let entry_points = reflect
+        .get_entry_points()
+        .expect("Failed to extract entry points");
+
+    assert_eq!(entry_points.len(), 2);
+    assert_eq!(entry_points[0].name, "main_a");
+    assert_eq!(entry_points[1].name, "main_b");
+    assert!(entry_points
+        .iter()
+        .all(|e| e.execution_model == spirv::ExecutionModel::GLCompute));
+
+    // Module-wide queries cannot tell the entry points apart
+    assert_eq!(reflect.get_compute_group_size(), Some((8, 8, 1)));
+    assert!(matches!(
+        reflect.get_push_constant_range(),
+        Err(ReflectError::TooManyPushConstants)
+    ));
+
+    let main_a = &entry_points[0];
+    let main_b = &entry_points[1];
+
+    assert_eq!(
+        reflect.get_compute_group_size_for_entry_point(main_a),
+        Some((8, 8, 1))
+    );
+    assert_eq!(
+        reflect.get_compute_group_size_for_entry_point(main_b),
+        Some((64, 1, 1))
+    );
+
+    assert_eq!(
+        reflect
+            .get_push_constant_range_for_entry_point(main_a)
+            .expect("Failed to extract push constants"),
+        Some(PushConstantInfo {
+            offset: 0,
+            size: 4,
+            stages: ShaderStageFlags::COMPUTE
+        })
+    );
+    assert_eq!(
+        reflect
+            .get_push_constant_range_for_entry_point(main_b)
+            .expect("Failed to extract push constants"),
+        Some(PushConstantInfo {
+            offset: 0,
+            size: 12,
+            stages: ShaderStageFlags::COMPUTE
+        })
+    );
+
+    let sets_a = reflect
+        .get_descriptor_sets_for_entry_point(main_a)
+        .expect("Failed to extract descriptor sets");
+    let sets_b = reflect
+        .get_descriptor_sets_for_entry_point(main_b)
+        .expect("Failed to extract descriptor sets");
+
+    assert_eq!(sets_a[&0].len(), 1);
+    assert_eq!(sets_a[&0][&0].name, "g_bufferA");
+    assert_eq!(sets_b[&0].len(), 1);
+    assert_eq!(sets_b[&0][&1].name, "g_bufferB");
+}
+
+#[test]
+fn static_usage() {
+    // SPIR-V 1.3 and older only list input and output variables in the entry point interface
+    let spirv = include_bytes!("entry_points_spirv1_3-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let entry_points = reflect
+        .get_entry_points()
+        .expect("Failed to extract entry points");
+    let main_a = &entry_points[0];
+    let main_b = &entry_points[1];
+
+    // Without an interface list every descriptor is visible, but only one is used by each entry
+    // point
+    let sets_a = reflect
+        .get_descriptor_sets_for_entry_point(main_a)
+        .expect("Failed to extract descriptor sets");
+    assert_eq!(sets_a[&0].len(), 2);
+    assert!(sets_a[&0][&0].is_statically_used);
+    assert!(!sets_a[&0][&1].is_statically_used);
+    assert_eq!(sets_a[&0][&0].stages, ShaderStageFlags::COMPUTE);
+    assert_eq!(sets_a[&0][&1].stages, ShaderStageFlags::NONE);
+
+    let sets_b = reflect
+        .get_descriptor_sets_for_entry_point(main_b)
+        .expect("Failed to extract descriptor sets");
+    assert_eq!(sets_b[&0].len(), 2);
+    assert!(!sets_b[&0][&0].is_statically_used);
+    assert!(sets_b[&0][&1].is_statically_used);
+
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    assert!(sets[&0][&0].is_statically_used);
+    assert!(sets[&0][&1].is_statically_used);
+
+    assert_eq!(
+        reflect
+            .get_push_constant_range_for_entry_point(main_b)
+            .expect("Failed to extract push constants"),
+        Some(PushConstantInfo {
+            offset: 0,
+            size: 12,
+            stages: ShaderStageFlags::COMPUTE
+        })
+    );
 }
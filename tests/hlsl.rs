@@ -1,4 +1,55 @@
 use rspirv_reflect::*;
+use std::sync::Arc;
+
+mod generated {
+    include!("codegen/push_constants_ps.rs");
+}
+
+/// A `RWTexture2D<uint>`
+fn rwtexture2d_uint() -> Option<ImageType> {
+    Some(ImageType {
+        sampled_type: ScalarType::Int {
+            width: 32,
+            signed: false,
+        },
+        dim: spirv::Dim::Dim2D,
+        depth: None,
+        arrayed: false,
+        multisampled: false,
+        sampled: Some(false),
+        format: spirv::ImageFormat::R32ui,
+    })
+}
+
+/// Layout of a `StructuredBuffer<T>` or `ByteAddressBuffer`, which are lowered to a block with a
+/// single, unnamed runtime array member
+fn runtime_array_block(name: &str, element: TypeLayout, stride: u32) -> StructLayout {
+    StructLayout {
+        name: name.to_string(),
+        size: 0,
+        members: vec![MemberLayout {
+            name: "".to_string(),
+            offset: 0,
+            size: 0,
+            ty: TypeLayout::Array {
+                element: Box::new(element),
+                length: None,
+                stride,
+            },
+        }],
+    }
+}
+
+fn uint() -> TypeLayout {
+    TypeLayout::Numeric(NumericType {
+        scalar: ScalarType::Int {
+            width: 32,
+            signed: false,
+        },
+        vector_size: 1,
+        column_count: 1,
+    })
+}
 
 #[test]
 fn bindings() {
@@ -11,6 +62,20 @@ fn bindings() {
 
     assert_eq!(reflect.get_compute_group_size(), Some((64, 1, 1)));
 
+    let entry_points = reflect
+        .get_entry_points()
+        .expect("Failed to extract entry points");
+    assert_eq!(entry_points.len(), 1);
+    assert_eq!(entry_points[0].name, "main");
+    assert_eq!(
+        entry_points[0].execution_model,
+        spirv::ExecutionModel::GLCompute
+    );
+    assert_eq!(
+        reflect.get_compute_group_size_for_entry_point(&entry_points[0]),
+        Some((64, 1, 1))
+    );
+
     let sets = reflect
         .get_descriptor_sets()
         .expect("Failed to extract descriptor sets");
@@ -32,7 +97,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_input".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: Some(runtime_array_block("type.StructuredBuffer.uint", uint(), 4)),
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -41,7 +113,18 @@ fn bindings() {
         DescriptorInfo {
             name: "g_output".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: Some(runtime_array_block(
+                "type.RWStructuredBuffer.uint",
+                uint(),
+                4
+            )),
+            image: None,
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::WRITE,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -50,7 +133,27 @@ fn bindings() {
         DescriptorInfo {
             name: "g_constant".to_string(),
             ty: DescriptorType::UNIFORM_BUFFER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: Some(StructLayout {
+                name: "type.ConstantBuffer.TestType".to_string(),
+                size: 16,
+                members: vec![MemberLayout {
+                    name: "asdf".to_string(),
+                    offset: 0,
+                    size: 16,
+                    ty: TypeLayout::Numeric(NumericType {
+                        scalar: ScalarType::Float { width: 32 },
+                        vector_size: 4,
+                        column_count: 1
+                    })
+                }]
+            }),
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -59,7 +162,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_bindlessInput".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::Unbounded
+            binding_count: BindingCount::Unbounded,
+            is_statically_used: true,
+            block: Some(runtime_array_block("type.StructuredBuffer.uint", uint(), 4)),
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -68,7 +178,22 @@ fn bindings() {
         DescriptorInfo {
             name: "g_texture2d".to_string(),
             ty: DescriptorType::SAMPLED_IMAGE,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: None,
+            image: Some(ImageType {
+                sampled_type: ScalarType::Float { width: 32 },
+                dim: spirv::Dim::Dim2D,
+                depth: None,
+                arrayed: false,
+                multisampled: false,
+                sampled: Some(true),
+                format: spirv::ImageFormat::Unknown
+            }),
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::SAMPLE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -77,7 +202,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_rwtexture2d".to_string(),
             ty: DescriptorType::STORAGE_IMAGE,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: None,
+            image: rwtexture2d_uint(),
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -86,7 +218,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_bindlessrwtexture2d".to_string(),
             ty: DescriptorType::STORAGE_IMAGE,
-            binding_count: BindingCount::Unbounded
+            binding_count: BindingCount::Unbounded,
+            is_statically_used: true,
+            block: None,
+            image: rwtexture2d_uint(),
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -95,7 +234,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_sampler".to_string(),
             ty: DescriptorType::SAMPLER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: None,
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::SAMPLE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -104,7 +250,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_byteAddressBuffer".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::Unbounded
+            binding_count: BindingCount::Unbounded,
+            is_statically_used: true,
+            block: Some(runtime_array_block("type.ByteAddressBuffer", uint(), 4)),
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -113,7 +266,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_rwbyteAddressBuffer".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: Some(runtime_array_block("type.RWByteAddressBuffer", uint(), 4)),
+            image: None,
+            access: AccessFlags::READ_WRITE,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -122,7 +282,22 @@ fn bindings() {
         DescriptorInfo {
             name: "g_inputArray".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::One
+            binding_count: BindingCount::One,
+            is_statically_used: true,
+            block: Some(runtime_array_block(
+                "type.StructuredBuffer.",
+                TypeLayout::Array {
+                    element: Box::new(uint()),
+                    length: Some(ArrayLength::Constant(4)),
+                    stride: 4
+                },
+                16
+            )),
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -131,7 +306,14 @@ fn bindings() {
         DescriptorInfo {
             name: "g_arrayOfInputs".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::StaticSized(4)
+            binding_count: BindingCount::StaticSized(4),
+            is_statically_used: true,
+            block: Some(runtime_array_block("type.StructuredBuffer.uint", uint(), 4)),
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 
@@ -140,7 +322,22 @@ fn bindings() {
         DescriptorInfo {
             name: "g_bindlessInputArray".to_string(),
             ty: DescriptorType::STORAGE_BUFFER,
-            binding_count: BindingCount::Unbounded
+            binding_count: BindingCount::Unbounded,
+            is_statically_used: true,
+            block: Some(runtime_array_block(
+                "type.StructuredBuffer.",
+                TypeLayout::Array {
+                    element: Box::new(uint()),
+                    length: Some(ArrayLength::Constant(4)),
+                    stride: 4
+                },
+                16
+            )),
+            image: None,
+            access: AccessFlags::READ,
+            used_access: AccessFlags::READ,
+            sampling: SamplingFlags::NONE,
+            stages: ShaderStageFlags::COMPUTE
         }
     );
 }
@@ -157,3 +354,280 @@ fn push_constants() {
 
     assert_eq!(range.size, 404);
 }
+
+#[test]
+fn push_constants_to_rust() {
+    let spirv = include_bytes!("push_constants_ps-hlsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let layout = reflect.get_push_constant_layout().unwrap().unwrap();
+
+    assert_eq!(
+        layout.to_rust("PushConstants").unwrap(),
+        include_str!("codegen/push_constants_ps.rs")
+    );
+
+    // The struct is padded to the 8-byte alignment of its `uint64_t` and `double` members,
+    // which does not fit in the push constant range
+    let push_constants = generated::PushConstants {
+        a: 1,
+        ..bytemuck::Zeroable::zeroed()
+    };
+    let bytes = bytemuck::bytes_of(&push_constants);
+    assert_eq!(bytes.len(), 408);
+    assert_eq!(generated::PushConstants::SIZE, 404);
+    let bytes = &bytes[..generated::PushConstants::SIZE];
+    assert_eq!(bytes.len() as u32, layout.size);
+    assert_eq!(bytes[..4], 1u32.to_ne_bytes());
+}
+
+#[test]
+fn push_constant_layout() {
+    let spirv = include_bytes!("push_constants_ps-hlsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let layout = reflect
+        .get_push_constant_layout()
+        .expect("failed to extract push constant layout")
+        .expect("defined push constants not detected");
+
+    dbg!(&layout);
+
+    let numeric = |scalar, vector_size| NumericType {
+        scalar,
+        vector_size,
+        column_count: 1,
+    };
+    let uint = ScalarType::Int {
+        width: 32,
+        signed: false,
+    };
+    let member = |name: &str, offset, size, ty| MemberLayout {
+        name: name.to_string(),
+        offset,
+        size,
+        ty,
+    };
+
+    assert_eq!(
+        layout,
+        StructLayout {
+            name: "type.PushConstant.PushConstant".to_string(),
+            size: 404,
+            members: vec![
+                member("a", 0, 4, TypeLayout::Numeric(numeric(uint, 1))),
+                member(
+                    "b",
+                    4,
+                    4,
+                    TypeLayout::Numeric(numeric(ScalarType::Float { width: 32 }, 1))
+                ),
+                // HLSL bools are lowered to uints in externally visible blocks
+                member("c", 8, 4, TypeLayout::Numeric(numeric(uint, 1))),
+                member(
+                    "d",
+                    16,
+                    8,
+                    TypeLayout::Numeric(numeric(
+                        ScalarType::Int {
+                            width: 64,
+                            signed: false
+                        },
+                        1
+                    ))
+                ),
+                member(
+                    "e",
+                    24,
+                    48,
+                    TypeLayout::Array {
+                        element: Box::new(TypeLayout::Numeric(numeric(
+                            ScalarType::Float { width: 64 },
+                            1
+                        ))),
+                        length: Some(ArrayLength::Constant(6)),
+                        stride: 8,
+                    }
+                ),
+                member(
+                    "f",
+                    72,
+                    320,
+                    TypeLayout::Array {
+                        element: Box::new(TypeLayout::Matrix {
+                            ty: NumericType {
+                                scalar: ScalarType::Float { width: 32 },
+                                vector_size: 4,
+                                column_count: 4,
+                            },
+                            stride: 16,
+                            row_major: true,
+                        }),
+                        length: Some(ArrayLength::Constant(5)),
+                        stride: 64,
+                    }
+                ),
+                member(
+                    "g",
+                    392,
+                    12,
+                    TypeLayout::Numeric(numeric(
+                        ScalarType::Int {
+                            width: 32,
+                            signed: true
+                        },
+                        3
+                    ))
+                ),
+            ],
+        }
+    );
+}
+
+#[test]
+fn push_constant_layout_rules() {
+    let spirv = include_bytes!("push_constants_ps-hlsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let layout = reflect.get_push_constant_layout().unwrap().unwrap();
+
+    // Compiled with `-fvk-use-scalar-layout`, which places the `float4x4` array right after
+    // the `float64_t` array instead of aligning it to 16 bytes
+    assert_eq!(layout.layout_rules(), [LayoutRule::Scalar]);
+    assert_eq!(
+        layout.validate(LayoutRule::Std430),
+        [
+            LayoutViolation {
+                member: "f".to_owned(),
+                decoration: spirv::Decoration::Offset,
+                actual: 72,
+                expected: 80,
+            },
+            LayoutViolation {
+                member: "g".to_owned(),
+                decoration: spirv::Decoration::Offset,
+                actual: 392,
+                expected: 400,
+            },
+        ]
+    );
+}
+
+#[test]
+fn interface_variables() {
+    let spirv = include_bytes!("push_constants_ps-hlsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let entry_points = reflect
+        .get_entry_points()
+        .expect("Failed to extract entry points");
+    let variables = reflect
+        .get_interface_variables(&entry_points[0])
+        .expect("Failed to extract interface variables");
+
+    let float4 = NumericType {
+        scalar: ScalarType::Float { width: 32 },
+        vector_size: 4,
+        column_count: 1,
+    };
+
+    assert_eq!(
+        variables,
+        vec![
+            InterfaceVariable {
+                name: "in.var.COLOR".to_string(),
+                storage_class: spirv::StorageClass::Input,
+                location: Some(0),
+                component: 0,
+                builtin: None,
+                ty: float4,
+                array_dims: vec![],
+                interpolation: InterpolationFlags::NONE,
+                patch: false,
+                per_primitive: false
+            },
+            InterfaceVariable {
+                name: "out.var.SV_TARGET".to_string(),
+                storage_class: spirv::StorageClass::Output,
+                location: Some(0),
+                component: 0,
+                builtin: None,
+                ty: float4,
+                array_dims: vec![],
+                interpolation: InterpolationFlags::NONE,
+                patch: false,
+                per_primitive: false
+            },
+        ]
+    );
+}
+
+#[test]
+fn vertex_input_attributes_of_fragment_shader() {
+    let spirv = include_bytes!("push_constants_ps-hlsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let entry_points = reflect
+        .get_entry_points()
+        .expect("Failed to extract entry points");
+
+    assert!(matches!(
+        reflect.get_vertex_input_attributes(&entry_points[0]),
+        Err(ReflectError::UnexpectedExecutionModel(
+            spirv::ExecutionModel::Fragment
+        ))
+    ));
+}
+
+#[test]
+fn resource_types() {
+    let spirv = include_bytes!("shader_cs-hlsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let types = reflect.get_types().expect("Failed to resolve types");
+
+    let uint = ScalarType::Int {
+        width: 32,
+        signed: false,
+    };
+
+    // ConstantBuffer<TestType>
+    assert!(types.values().any(|ty| *ty
+        == ReflectType::Struct(Arc::new(StructType {
+            name: "type.ConstantBuffer.TestType".to_string(),
+            members: vec![StructMember {
+                name: "asdf".to_string(),
+                ty: ReflectType::Vector {
+                    scalar: ScalarType::Float { width: 32 },
+                    size: 4,
+                },
+                offset: Some(0),
+            }],
+        }))));
+
+    // StructuredBuffer<uint[4]>
+    assert!(types.values().any(|ty| *ty
+        == ReflectType::RuntimeArray {
+            element: Box::new(ReflectType::Array {
+                element: Box::new(ReflectType::Scalar(uint)),
+                length: ArrayLength::Constant(4),
+                stride: Some(4),
+            }),
+            stride: Some(16),
+        }));
+
+    // RWTexture2D<uint>
+    assert!(types.values().any(|ty| *ty
+        == ReflectType::Image(ImageType {
+            sampled_type: uint,
+            dim: spirv::Dim::Dim2D,
+            depth: None,
+            arrayed: false,
+            multisampled: false,
+            sampled: Some(false),
+            format: spirv::ImageFormat::R32ui,
+        })));
+
+    assert!(types.values().any(|ty| *ty == ReflectType::Sampler));
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "json")]
+
+mod common;
+
+use rspirv_reflect::*;
+use serde_json::json;
+
+#[test]
+fn golden() {
+    // Generated by `spirv-cross --reflect` in `compile_shaders.sh`
+    for (spirv, json) in [
+        (
+            &include_bytes!("shader-glsl.spv")[..],
+            include_str!("shader-glsl.json"),
+        ),
+        (
+            include_bytes!("workgroup_size-glsl.spv"),
+            include_str!("workgroup_size-glsl.json"),
+        ),
+        (
+            include_bytes!("entry_points_spirv1_4-glsl.spv"),
+            include_str!("entry_points_spirv1_4-glsl.json"),
+        ),
+        (
+            include_bytes!("push_constants-glsl.spv"),
+            include_str!("push_constants-glsl.json"),
+        ),
+        (
+            include_bytes!("layouts-glsl.spv"),
+            include_str!("layouts-glsl.json"),
+        ),
+        (
+            include_bytes!("shader_cs-hlsl.spv"),
+            include_str!("shader_cs-hlsl.json"),
+        ),
+    ] {
+        let reflect = Reflection::new_from_spirv(spirv)
+            .expect("Failed to create reflection module from spirv code");
+
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(reflect.to_json().unwrap(), expected);
+    }
+}
+
+#[test]
+fn vertex_shader() {
+    let spirv = include_bytes!("push_constants-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let json = reflect.to_json().unwrap();
+    assert_eq!(
+        json["entryPoints"],
+        json!([{ "name": "main", "mode": "vert" }])
+    );
+    // Builtin outputs such as `gl_Position` are not reflected
+    assert_eq!(
+        json["outputs"],
+        json!([{ "type": "vec2", "name": "uv", "location": 0 }])
+    );
+    assert_eq!(
+        json["push_constants"],
+        json!([{ "type": "_11", "name": "registers", "push_constant": true }])
+    );
+    assert_eq!(json["types"]["_11"]["name"], "Registers");
+}
+
+#[test]
+fn local_size_id() {
+    // glslang cannot target SPIR-V 1.6, where `LocalSizeId` replaces the `WorkgroupSize` builtin
+    let mut b = common::builder();
+    let void = b.type_void();
+    let void_fn = b.type_function(void, vec![]);
+    let uint = b.type_int(32, 0);
+    let x = b.spec_constant_bit32(uint, 32);
+    b.decorate(
+        x,
+        spirv::Decoration::SpecId,
+        [rspirv::dr::Operand::LiteralBit32(7)],
+    );
+    let y = b.constant_bit32(uint, 2);
+    let main = b
+        .begin_function(void, None, spirv::FunctionControl::NONE, void_fn)
+        .unwrap();
+    b.begin_block(None).unwrap();
+    b.ret().unwrap();
+    b.end_function().unwrap();
+    b.entry_point(spirv::ExecutionModel::GLCompute, main, "main", []);
+    b.execution_mode_id(main, spirv::ExecutionMode::LocalSizeId, [x, y, y]);
+
+    // rspirv cannot parse `OpExecutionModeId`, so the module is reflected without assembling it
+    let reflect = Reflection::new(b.module());
+
+    let json = reflect.to_json().unwrap();
+    assert_eq!(
+        json["entryPoints"],
+        json!([{
+            "name": "main",
+            "mode": "comp",
+            "workgroup_size": [7, 2, 2],
+            "workgroup_size_is_spec_constant_id": [true, false, false],
+        }])
+    );
+}
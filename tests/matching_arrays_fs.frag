@@ -0,0 +1,12 @@
+#version 450
+
+// Reads the second element of `weights` from `matching_arrays_vs.vert` as a separate variable
+layout(location = 1) in vec4 weight;
+layout(location = 4) in vec4 color;
+
+layout(location = 0) out vec4 result;
+
+void main()
+{
+    result = weight * color;
+}
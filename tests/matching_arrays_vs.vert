@@ -0,0 +1,11 @@
+#version 450
+
+layout(location = 0) out vec4 weights[3];
+layout(location = 4) out vec4 color;
+
+void main()
+{
+    weights = vec4[3](vec4(0.0), vec4(0.5), vec4(1.0));
+    color = vec4(1.0);
+    gl_Position = vec4(0.0);
+}
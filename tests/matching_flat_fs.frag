@@ -0,0 +1,13 @@
+#version 450
+
+// Only the fragment shader inputs decide about interpolation, so `flat` does not have to be
+// repeated on the vertex shader output of `uv`
+layout(location = 0) flat in vec2 uv;
+layout(location = 1) flat in vec4 color;
+
+layout(location = 0) out vec4 result;
+
+void main()
+{
+    result = vec4(uv, 0.0, 0.0) + color;
+}
@@ -0,0 +1,15 @@
+#version 450
+
+// Reads `color` without the `flat` qualifier of `matching_vs.vert`, `normal` as a different type
+// and `tangent` that is not written at all
+layout(location = 0) in vec2 uv;
+layout(location = 1) in vec4 color;
+layout(location = 2) in vec4 normal;
+layout(location = 4) in vec4 tangent;
+
+layout(location = 0) out vec4 result;
+
+void main()
+{
+    result = vec4(uv, 0.0, 0.0) + color + normal + tangent;
+}
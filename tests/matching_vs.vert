@@ -0,0 +1,15 @@
+#version 450
+
+layout(location = 0) out vec2 uv;
+layout(location = 1) flat out vec4 color;
+layout(location = 2) out vec3 normal;
+layout(location = 3) out vec4 extra;
+
+void main()
+{
+    uv = vec2(0.0);
+    color = vec4(1.0);
+    normal = vec3(0.0, 0.0, 1.0);
+    extra = vec4(0.0);
+    gl_Position = vec4(0.0);
+}
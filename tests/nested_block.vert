@@ -0,0 +1,30 @@
+#version 450
+
+struct Light {
+    vec3 direction;
+    vec4 color;
+};
+
+struct Material {
+    vec2 uv;
+    Light lights[2];
+};
+
+// Nested structs are flattened into one variable per member, with consecutive locations
+layout(location = 1) out Varyings {
+    vec4 position;
+    flat Material material;
+    float depth;
+} vs_out;
+
+void main()
+{
+    vs_out.position = vec4(0.0);
+    vs_out.material.uv = vec2(0.0);
+    vs_out.material.lights[0].direction = vec3(0.0);
+    vs_out.material.lights[0].color = vec4(1.0);
+    vs_out.material.lights[1].direction = vec3(0.0);
+    vs_out.material.lights[1].color = vec4(1.0);
+    vs_out.depth = 0.0;
+    gl_Position = vec4(0.0);
+}
@@ -0,0 +1,37 @@
+#version 450
+#extension GL_EXT_scalar_block_layout : require
+
+// The same members laid out by every packing rule that GLSL supports
+layout(set = 0, binding = 0, std140) uniform Std140
+{
+    float a[2];
+    vec3 b;
+    float c;
+    mat2 d;
+}
+std140_block;
+
+layout(set = 0, binding = 1, std430) buffer Std430
+{
+    float a[2];
+    vec3 b;
+    float c;
+    mat2 d;
+}
+std430_block;
+
+layout(set = 0, binding = 2, scalar) buffer Scalar
+{
+    float a[2];
+    vec3 b;
+    float c;
+    mat2 d;
+}
+scalar_block;
+
+layout(location = 0) out vec4 color;
+
+void main()
+{
+    color = vec4(std140_block.c + std430_block.c + scalar_block.c);
+}
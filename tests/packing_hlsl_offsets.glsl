@@ -0,0 +1,18 @@
+#version 450
+
+// Compiled with `--hlsl-offsets`, which packs `b` into the register of `a` and moves `c` to the
+// next register rather than letting it straddle two of them
+layout(set = 0, binding = 0) uniform HlslOffsets
+{
+    float a;
+    vec2 b;
+    vec3 c;
+}
+hlsl_offsets;
+
+layout(location = 0) out vec4 color;
+
+void main()
+{
+    color = vec4(hlsl_offsets.c, hlsl_offsets.a);
+}
@@ -0,0 +1,46 @@
+use rspirv_reflect::*;
+
+#[test]
+fn distinct_push_constant_ranges() {
+    let vertex = Reflection::new_from_spirv(include_bytes!("push_constants-glsl.spv"))
+        .expect("Failed to create reflection module from spirv code");
+    let fragment = Reflection::new_from_spirv(include_bytes!("push_constants_ps-hlsl.spv"))
+        .expect("Failed to create reflection module from spirv code");
+
+    let pipeline =
+        PipelineReflection::new(&[vertex, fragment]).expect("Failed to merge pipeline stages");
+
+    assert_eq!(
+        pipeline.push_constant_ranges,
+        vec![
+            PushConstantInfo {
+                stages: ShaderStageFlags::VERTEX,
+                offset: 0,
+                size: 16,
+            },
+            PushConstantInfo {
+                stages: ShaderStageFlags::FRAGMENT,
+                offset: 0,
+                size: 404,
+            },
+        ]
+    );
+}
+
+#[test]
+fn shader_stage_flags() {
+    assert_eq!(
+        ShaderStageFlags::from_execution_model(spirv::ExecutionModel::MeshNV).unwrap(),
+        ShaderStageFlags::MESH_EXT
+    );
+    assert_eq!(
+        ShaderStageFlags::from_execution_model(spirv::ExecutionModel::RayGenerationKHR).unwrap(),
+        ShaderStageFlags::RAYGEN_KHR
+    );
+    assert!(ShaderStageFlags::from_execution_model(spirv::ExecutionModel::Kernel).is_err());
+
+    assert_eq!(
+        format!("{:?}", ShaderStageFlags::ALL_GRAPHICS),
+        "VERTEX | TESSELLATION_CONTROL | TESSELLATION_EVALUATION | GEOMETRY | FRAGMENT"
+    );
+}
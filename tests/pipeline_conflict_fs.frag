@@ -0,0 +1,15 @@
+#version 450
+
+// A storage buffer at the binding of the uniform buffer in `pipeline_vs.vert`
+layout(set = 0, binding = 0) buffer Storage
+{
+    float value;
+}
+storage;
+
+layout(location = 0) out vec4 color;
+
+void main()
+{
+    color = vec4(storage.value);
+}
@@ -0,0 +1,21 @@
+#version 450
+
+// Conflicts with both the uniform buffer and the single storage buffer in `pipeline_vs.vert`
+layout(set = 0, binding = 0) buffer Storage
+{
+    float value;
+}
+storage;
+
+layout(set = 0, binding = 1) buffer Storages
+{
+    float value;
+}
+storages[2];
+
+layout(location = 0) out vec4 color;
+
+void main()
+{
+    color = vec4(storage.value + storages[1].value);
+}
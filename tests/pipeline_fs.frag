@@ -0,0 +1,33 @@
+#version 450
+
+layout(push_constant) uniform PushConstants
+{
+    float scale;
+}
+push_constants;
+
+layout(set = 0, binding = 0) uniform Uniforms
+{
+    float value;
+}
+uniforms;
+
+layout(set = 0, binding = 1) readonly buffer Storage
+{
+    float value;
+}
+storage;
+
+// Declared but not used by any stage
+layout(set = 0, binding = 2) buffer Unused
+{
+    float value;
+}
+unused;
+
+layout(location = 0) out vec4 color;
+
+void main()
+{
+    color = vec4(uniforms.value * storage.value * push_constants.scale);
+}
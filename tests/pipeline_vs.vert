@@ -0,0 +1,25 @@
+#version 450
+
+layout(push_constant) uniform PushConstants
+{
+    float scale;
+}
+push_constants;
+
+layout(set = 0, binding = 0) uniform Uniforms
+{
+    float value;
+}
+uniforms;
+
+// Declared but not used by this stage
+layout(set = 0, binding = 1) buffer Storage
+{
+    float value;
+}
+storage;
+
+void main()
+{
+    gl_Position = vec4(uniforms.value * push_constants.scale);
+}
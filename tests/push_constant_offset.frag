@@ -0,0 +1,16 @@
+#version 450
+
+// Starts at byte 16, leaving room for the push constants of an earlier stage
+layout(push_constant) uniform Registers
+{
+    layout(offset = 16) vec2 bias;
+    layout(offset = 24) float scale;
+}
+registers;
+
+layout(location = 0) out vec4 color;
+
+void main()
+{
+    color = vec4(registers.scale);
+}
@@ -0,0 +1,69 @@
+use rspirv_reflect::*;
+
+fn to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+#[test]
+fn remap_bindings() {
+    let spirv = include_bytes!("shader_cs-hlsl.spv");
+    let mut reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let original = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    // Pack all descriptors into a single set, similar to `-fvk-*-shift`
+    reflect
+        .remap_bindings(|set, binding, info| {
+            let shift = if info.ty == DescriptorType::SAMPLER {
+                100
+            } else {
+                0
+            };
+            (0, set * 10 + binding + shift)
+        })
+        .expect("Failed to remap bindings");
+
+    let patched = Reflection::new_from_spirv(&to_bytes(&reflect.assemble()))
+        .expect("Failed to create reflection module from patched spirv code");
+    let sets = patched
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    assert_eq!(sets.len(), 1);
+    assert_eq!(
+        sets[&0].len(),
+        original
+            .values()
+            .map(|bindings| bindings.len())
+            .sum::<usize>()
+    );
+    for (set, bindings) in &original {
+        for (binding, info) in bindings {
+            let shift = if info.ty == DescriptorType::SAMPLER {
+                100
+            } else {
+                0
+            };
+            assert_eq!(&sets[&0][&(set * 10 + binding + shift)], info);
+        }
+    }
+}
+
+#[test]
+fn remap_binding_collision() {
+    let spirv = include_bytes!("shader-glsl.spv");
+    let mut reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let original = reflect.assemble();
+
+    assert!(matches!(
+        reflect.remap_bindings(|set, _, _| (set, 0)),
+        Err(ReflectError::RemappedBindingCollision(_, 0))
+    ));
+
+    // The module is left untouched when remapping fails
+    assert_eq!(reflect.assemble(), original);
+}
@@ -0,0 +1,16 @@
+#version 450
+
+// Neither texture is declared as a depth image, only its use reveals the shadow map
+layout(set = 0, binding = 0) uniform texture2D shadow_map;
+layout(set = 0, binding = 1) uniform samplerShadow shadow_sampler;
+layout(set = 0, binding = 2) uniform texture2D color_texture;
+layout(set = 0, binding = 3) uniform sampler linear_sampler;
+layout(set = 0, binding = 4) uniform sampler unused_sampler;
+
+layout(location = 0) out vec4 color;
+
+void main()
+{
+    float shadow = texture(sampler2DShadow(shadow_map, shadow_sampler), vec3(0.5));
+    color = texture(sampler2D(color_texture, linear_sampler), vec2(0.5)) * shadow;
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "serde")]
+
+use rspirv_reflect::*;
+use std::collections::BTreeMap;
+
+#[test]
+fn round_trip() {
+    let spirv = include_bytes!("shader-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+    let json = serde_json::to_string(&sets).unwrap();
+    assert_eq!(
+        serde_json::from_str::<BTreeMap<u32, BTreeMap<u32, DescriptorInfo>>>(&json).unwrap(),
+        sets
+    );
+
+    let types = reflect.get_types().unwrap();
+    let json = serde_json::to_string(&types).unwrap();
+    assert_eq!(
+        serde_json::from_str::<BTreeMap<u32, ReflectType>>(&json).unwrap(),
+        types
+    );
+
+    let requirements = reflect.get_vulkan_requirements().unwrap();
+    let json = serde_json::to_string(&requirements).unwrap();
+    assert_eq!(
+        serde_json::from_str::<VulkanRequirements>(&json).unwrap(),
+        requirements
+    );
+}
+
+#[test]
+fn unknown_descriptor_type() {
+    let ty = DescriptorType(1_000_351_000);
+    let json = serde_json::to_string(&ty).unwrap();
+    assert_eq!(json, "1000351000");
+    assert_eq!(serde_json::from_str::<DescriptorType>(&json).unwrap(), ty);
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use rspirv_reflect::*;
+
+#[test]
+fn spec_constant_without_spec_id() {
+    // Compilers decorate every `OpSpecConstant` with a `SpecId`, without one it cannot be
+    // specialized
+    let mut b = common::builder();
+    let int = b.type_int(32, 1);
+    let id = b.spec_constant_bit32(int, 7);
+    b.name(id, "INTERNAL");
+
+    let reflect = Reflection::new(b.module());
+    assert_eq!(
+        reflect
+            .get_specialization_constants()
+            .expect("Failed to extract specialization constants"),
+        vec![]
+    );
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use rspirv_reflect::*;
+
+#[test]
+fn spec_constant_without_spec_id_array_length() {
+    // Compilers decorate every `OpSpecConstant` with a `SpecId`, without one it cannot be
+    // specialized and behaves like a regular constant
+    let mut b = common::builder();
+    let uint = b.type_int(32, 0);
+    let float = b.type_float(32);
+    let length = b.spec_constant_bit32(uint, 2);
+    let array = b.type_array(float, length);
+
+    let reflect = Reflection::new(b.module());
+    assert_eq!(
+        reflect.get_type(array).unwrap(),
+        ReflectType::Array {
+            element: Box::new(ReflectType::Scalar(ScalarType::Float { width: 32 })),
+            length: ArrayLength::Constant(2),
+            stride: None,
+        }
+    );
+}
@@ -0,0 +1,14 @@
+#version 450
+
+// Declared out of order on purpose
+layout(location = 1) in vec2 in_uv;
+layout(location = 0) in vec3 in_position;
+layout(location = 2) in uvec4 in_color;
+layout(location = 3) in mat4 in_transform;
+layout(location = 7) in dvec4 in_weights;
+
+void main()
+{
+    gl_Position = in_transform * vec4(in_position, float(gl_VertexIndex)) +
+                  vec4(in_uv, vec2(in_color.xy)) + vec4(in_weights);
+}
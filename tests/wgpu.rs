@@ -0,0 +1,118 @@
+#![cfg(feature = "wgpu")]
+
+use rspirv_reflect::*;
+use std::num::{NonZeroU32, NonZeroU64};
+use wgpu_types as wgt;
+
+const MAX_UNBOUNDED_COUNT: NonZeroU32 = NonZeroU32::new(64).unwrap();
+
+#[test]
+fn bind_group_layout_entries() {
+    let spirv = include_bytes!("shader-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    assert_eq!(
+        wgpu_bind_group_layout_entries(&sets[&0], MAX_UNBOUNDED_COUNT).unwrap(),
+        vec![wgt::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgt::ShaderStages::COMPUTE,
+            ty: wgt::BindingType::Buffer {
+                ty: wgt::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: NonZeroU64::new(20),
+            },
+            count: None,
+        }]
+    );
+
+    assert_eq!(
+        wgpu_bind_group_layout_entries(&sets[&1], MAX_UNBOUNDED_COUNT)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.ty)
+            .collect::<Vec<_>>(),
+        [
+            wgt::StorageTextureAccess::ReadOnly,
+            wgt::StorageTextureAccess::WriteOnly,
+            wgt::StorageTextureAccess::ReadWrite,
+        ]
+        .iter()
+        .map(|&access| wgt::BindingType::StorageTexture {
+            access,
+            format: wgt::TextureFormat::Rgba32Float,
+            view_dimension: wgt::TextureViewDimension::D2,
+        })
+        .collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        wgpu_bind_group_layout_entries(&sets[&3], MAX_UNBOUNDED_COUNT).unwrap()[1],
+        wgt::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgt::ShaderStages::NONE,
+            ty: wgt::BindingType::Texture {
+                sample_type: wgt::TextureSampleType::Float { filterable: true },
+                view_dimension: wgt::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: NonZeroU32::new(10),
+        }
+    );
+
+    assert_eq!(
+        sets[&6][&2].to_wgpu_entry(2, MAX_UNBOUNDED_COUNT).unwrap(),
+        wgt::BindGroupLayoutEntry {
+            binding: 2,
+            visibility: wgt::ShaderStages::COMPUTE,
+            ty: wgt::BindingType::Buffer {
+                ty: wgt::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: NonZeroU64::new(4),
+            },
+            count: Some(MAX_UNBOUNDED_COUNT),
+        }
+    );
+}
+
+#[test]
+fn specialized_min_binding_size() {
+    let spirv = include_bytes!("layouts-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    let min_binding_sizes = wgpu_bind_group_layout_entries(&sets[&0], MAX_UNBOUNDED_COUNT)
+        .unwrap()
+        .into_iter()
+        .map(|entry| match entry.ty {
+            wgt::BindingType::Buffer {
+                min_binding_size, ..
+            } => min_binding_size,
+            ty => panic!("Unexpected {:?}", ty),
+        })
+        .collect::<Vec<_>>();
+    // The size of `SpecConstantArrays` depends on `COUNT`, which may be overridden
+    assert_eq!(min_binding_sizes, [None, NonZeroU64::new((16 << 22) - 12)]);
+}
+
+#[test]
+fn unsupported_descriptor_type() {
+    let spirv = include_bytes!("shader-glsl.spv");
+    let reflect = Reflection::new_from_spirv(spirv)
+        .expect("Failed to create reflection module from spirv code");
+    let sets = reflect
+        .get_descriptor_sets()
+        .expect("Failed to extract descriptor sets");
+
+    // Combined image samplers have no wgpu equivalent
+    assert!(matches!(
+        wgpu_bind_group_layout_entries(&sets[&5], MAX_UNBOUNDED_COUNT),
+        Err(ReflectError::UnsupportedWgpuBinding(0, _))
+    ));
+}